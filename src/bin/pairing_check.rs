@@ -0,0 +1,248 @@
+//!
+//! # `pairing-check`
+//!
+//! Check all paired tokens (`{}`, `[]`, `()` etc.) in source files by using the
+//! `CodeTokenPairingValidator`, it's designed for the pre-commit hooks:
+//!
+//! ```bash
+//! pairing-check src/main.rs "src/**/*.rs" "config/*.json"
+//! pairing-check --format json --profile rust build.rs
+//! ```
+//!
+//! The language profile is picked by the file extension, unless `--profile` is given. Files
+//! with an unknown extension use the `plain` profile.
+//!
+//! Exit code:
+//!
+//! - `0`: All files passed.
+//! - `1`: At least one file failed the check.
+//! - `2`: Bad arguments, or some file can't be read.
+//!
+#![allow(clippy::empty_docs)]
+
+use data_structure_implementation_by_rust::stack::source_code_token_pairing_validator::diagnostic::Severity;
+use data_structure_implementation_by_rust::stack::source_code_token_pairing_validator::language_profile::LanguageProfile;
+use data_structure_implementation_by_rust::stack::source_code_token_pairing_validator::{
+    CodeTokenPairingValidator, CodeTokenValidateResult, ColumnUnit,
+};
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use std::process::exit;
+
+const USAGE: &str = "Usage: pairing-check [OPTIONS] <FILE_OR_GLOB>...
+
+Options:
+    --format <text|json>            Output format, default is `text`
+    --profile <NAME>                Use `plain`, `rust`, `c`, `json` or `python` for all files,
+                                    default is picking by file extension
+    --columns <char|grapheme>       How to count the column number, default is `char`
+    -h, --help                      Print this help";
+
+///
+#[derive(Debug, PartialEq)]
+enum OutputFormat {
+    Text,
+    Json,
+}
+
+///
+#[derive(Debug)]
+struct Options {
+    format: OutputFormat,
+    profile: Option<LanguageProfile>,
+    column_unit: ColumnUnit,
+    patterns: Vec<String>,
+}
+
+/// The check result of a single file
+#[derive(Debug)]
+enum FileResult {
+    Checked(CodeTokenValidateResult),
+    Error(String),
+}
+
+///
+fn parse_options(args: Vec<String>) -> Result<Options, String> {
+    let mut options = Options {
+        format: OutputFormat::Text,
+        profile: None,
+        column_unit: ColumnUnit::UnicodeScalar,
+        patterns: vec![],
+    };
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                exit(0);
+            }
+            "--format" => {
+                options.format = match args.next().as_deref() {
+                    Some("text") => OutputFormat::Text,
+                    Some("json") => OutputFormat::Json,
+                    other => return Err(format!("Invalid '--format' value: {:?}", other)),
+                };
+            }
+            "--profile" => {
+                let name = args.next().unwrap_or_default();
+                options.profile = Some(
+                    LanguageProfile::by_name(&name)
+                        .ok_or_else(|| format!("Unknown '--profile' value: '{}'", name))?,
+                );
+            }
+            "--columns" => {
+                options.column_unit = match args.next().as_deref() {
+                    Some("char") => ColumnUnit::UnicodeScalar,
+                    Some("grapheme") => ColumnUnit::Grapheme,
+                    other => return Err(format!("Invalid '--columns' value: {:?}", other)),
+                };
+            }
+            _ if arg.starts_with("--") => return Err(format!("Unknown option: '{}'", arg)),
+            _ => options.patterns.push(arg),
+        }
+    }
+
+    if options.patterns.is_empty() {
+        return Err("No file to check".to_string());
+    }
+
+    Ok(options)
+}
+
+/// Expand the glob patterns, plain file paths are kept as they are.
+fn expand_patterns(patterns: &[String]) -> Result<Vec<PathBuf>, String> {
+    let mut files = Vec::<PathBuf>::new();
+
+    for pattern in patterns {
+        if !pattern.contains(['*', '?', '[']) {
+            files.push(PathBuf::from(pattern));
+            continue;
+        }
+
+        let paths = glob::glob(pattern).map_err(|error| format!("'{}': {}", pattern, error))?;
+        let count_before = files.len();
+        for path in paths {
+            let path = path.map_err(|error| error.to_string())?;
+            if path.is_file() {
+                files.push(path);
+            }
+        }
+
+        if files.len() == count_before {
+            return Err(format!("'{}': No file matches", pattern));
+        }
+    }
+
+    Ok(files)
+}
+
+///
+fn check_file(options: &Options, file: &Path) -> FileResult {
+    let profile = options.profile.clone().unwrap_or_else(|| {
+        file.extension()
+            .and_then(|extension| LanguageProfile::by_extension(&extension.to_string_lossy()))
+            .unwrap_or_default()
+    });
+
+    let validator =
+        CodeTokenPairingValidator::with_profile(profile).with_column_unit(options.column_unit);
+
+    match validator.check_file(file) {
+        Ok(result) => FileResult::Checked(result),
+        Err(error) => FileResult::Error(error.to_string()),
+    }
+}
+
+///
+fn print_text(file: &Path, result: &FileResult) {
+    match result {
+        FileResult::Checked(CodeTokenValidateResult::Passed) => {}
+        FileResult::Checked(CodeTokenValidateResult::Fail(diagnostic)) => {
+            let position = diagnostic.position();
+            let severity = match diagnostic.severity {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+            };
+            println!(
+                "{}:{}:{}: {}: {}",
+                file.display(),
+                position.line_number,
+                position.column_number,
+                severity,
+                diagnostic.message()
+            );
+        }
+        FileResult::Error(error) => eprintln!("{}: error: {}", file.display(), error),
+    }
+}
+
+///
+fn result_to_json(file: &Path, result: &FileResult) -> serde_json::Value {
+    match result {
+        FileResult::Checked(CodeTokenValidateResult::Passed) => json!({
+            "file": file.display().to_string(),
+            "status": "passed",
+        }),
+        FileResult::Checked(CodeTokenValidateResult::Fail(diagnostic)) => json!({
+            "file": file.display().to_string(),
+            "status": "failed",
+            "line": diagnostic.position().line_number,
+            "column": diagnostic.position().column_number,
+            "byteOffset": diagnostic.position().byte_offset,
+            "message": diagnostic.to_string(),
+            "diagnostic": diagnostic,
+        }),
+        FileResult::Error(error) => json!({
+            "file": file.display().to_string(),
+            "status": "error",
+            "message": error,
+        }),
+    }
+}
+
+fn main() {
+    let options = match parse_options(std::env::args().skip(1).collect()) {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("pairing-check: {}\n\n{}", error, USAGE);
+            exit(2);
+        }
+    };
+
+    let files = match expand_patterns(&options.patterns) {
+        Ok(files) => files,
+        Err(error) => {
+            eprintln!("pairing-check: {}", error);
+            exit(2);
+        }
+    };
+
+    let mut has_failure = false;
+    let mut has_error = false;
+    let mut json_results = Vec::<serde_json::Value>::new();
+
+    for file in files.iter() {
+        let result = check_file(&options, file);
+        match &result {
+            FileResult::Checked(CodeTokenValidateResult::Passed) => {}
+            FileResult::Checked(CodeTokenValidateResult::Fail(..)) => has_failure = true,
+            FileResult::Error(_) => has_error = true,
+        }
+
+        match options.format {
+            OutputFormat::Text => print_text(file, &result),
+            OutputFormat::Json => json_results.push(result_to_json(file, &result)),
+        }
+    }
+
+    if options.format == OutputFormat::Json {
+        println!("{}", serde_json::Value::Array(json_results));
+    }
+
+    if has_error {
+        exit(2);
+    } else if has_failure {
+        exit(1);
+    }
+}
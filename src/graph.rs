@@ -1 +1,15 @@
+pub mod all_pairs_shortest_path;
+pub mod biconnectivity;
+pub mod connectivity;
+pub mod cycle;
+pub mod directed_graph;
+pub mod filtered_search;
+mod graph_file;
+pub mod graph_load_error;
+pub mod max_flow;
+pub mod shortest_path;
+pub mod spanning_tree;
+pub mod topological;
+pub mod traversal;
 pub mod undirected_graph;
+pub mod weight;
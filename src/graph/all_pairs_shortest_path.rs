@@ -0,0 +1,131 @@
+use super::shortest_path::{find_negative_cycle, NegativeCycle, ShortestPath};
+use super::undirected_graph::{Graph, NodeId};
+use super::weight::Weight;
+use serde::de::DeserializeOwned;
+use std::fmt::Debug;
+
+///
+/// # `AllPairsShortestPaths`
+///
+/// The shortest distance between every two nodes, and the next node on each shortest path to
+/// rebuild the whole path. The distance from a node to itself is `zero()`, the unreachable
+/// pairs don't have any distance.
+///
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct AllPairsShortestPaths<W = usize> {
+    // All nodes in the `NodeId` order, both matrices are indexed by the position in it
+    nodes: Vec<NodeId>,
+
+    // `distances[a][b]` is the shortest distance from `nodes[a]` to `nodes[b]`
+    distances: Vec<Vec<Option<W>>>,
+
+    // `next_positions[a][b]` is the position of the node after `nodes[a]` on that shortest path
+    next_positions: Vec<Vec<Option<usize>>>,
+}
+
+///
+impl<W: Weight> AllPairsShortestPaths<W> {
+    /// `None` if `to` isn't reachable from `from` or any of them doesn't exist.
+    pub fn distance(&self, from: NodeId, to: NodeId) -> Option<W> {
+        let (from_position, to_position) = (self.position_of(from)?, self.position_of(to)?);
+        self.distances[from_position][to_position]
+    }
+
+    /// Follow the next nodes from `from` to `to`, `None` if `to` isn't reachable from `from` or
+    /// any of them doesn't exist.
+    pub fn path(&self, from: NodeId, to: NodeId) -> Option<ShortestPath<W>> {
+        let (from_position, to_position) = (self.position_of(from)?, self.position_of(to)?);
+        let cost = self.distances[from_position][to_position]?;
+
+        let mut nodes = vec![from];
+        let mut current_position = from_position;
+        while current_position != to_position {
+            current_position = self.next_positions[current_position][to_position].unwrap();
+            nodes.push(self.nodes[current_position]);
+        }
+
+        Some(ShortestPath { cost, nodes })
+    }
+
+    ///
+    fn position_of(&self, node_index: NodeId) -> Option<usize> {
+        self.nodes.binary_search(&node_index).ok()
+    }
+}
+
+/// Floyd-Warshall algorithm, it works with the negative weights. Fails if there is any negative
+/// cycle, which is found by `find_negative_cycle` before the `O(n^3)` loop.
+///
+/// A path only goes through the node `k` if it's strictly shorter than the best path without
+/// `k`, and `k` goes in the `NodeId` order. So the same graph always gives back the same paths.
+pub(crate) fn floyd_warshall<T, G>(
+    graph: &G,
+) -> Result<AllPairsShortestPaths<G::Weight>, NegativeCycle>
+where
+    T: Debug + DeserializeOwned,
+    G: Graph<T>,
+{
+    if let Some(negative_cycle) = find_negative_cycle(graph) {
+        return Err(negative_cycle);
+    }
+
+    let nodes = graph
+        .nodes()
+        .map(|(node_index, _)| node_index)
+        .collect::<Vec<NodeId>>();
+    let nodes_len = nodes.len();
+    let mut all_pairs = AllPairsShortestPaths {
+        distances: vec![vec![None; nodes_len]; nodes_len],
+        next_positions: vec![vec![None; nodes_len]; nodes_len],
+        nodes,
+    };
+
+    for position in 0..nodes_len {
+        all_pairs.distances[position][position] = Some(G::Weight::zero());
+        all_pairs.next_positions[position][position] = Some(position);
+    }
+
+    // The direct edges, the smallest weight wins if there are more than one of them
+    for from_position in 0..nodes_len {
+        for (neighbor_index, weight, _) in graph.neighbors(all_pairs.nodes[from_position]) {
+            let to_position = all_pairs.position_of(neighbor_index).unwrap();
+            let is_shorter = match all_pairs.distances[from_position][to_position] {
+                Some(old_distance) => weight < old_distance,
+                None => true,
+            };
+            if is_shorter {
+                all_pairs.distances[from_position][to_position] = Some(weight);
+                all_pairs.next_positions[from_position][to_position] = Some(to_position);
+            }
+        }
+    }
+
+    for k in 0..nodes_len {
+        for a in 0..nodes_len {
+            let a_to_k = match all_pairs.distances[a][k] {
+                Some(a_to_k) => a_to_k,
+                None => continue,
+            };
+
+            for b in 0..nodes_len {
+                // Too far to be counted, treat it as unreachable through `k`
+                let new_distance =
+                    match all_pairs.distances[k][b].and_then(|k_to_b| a_to_k.checked_add(k_to_b)) {
+                        Some(new_distance) => new_distance,
+                        None => continue,
+                    };
+
+                let is_shorter = match all_pairs.distances[a][b] {
+                    Some(old_distance) => new_distance < old_distance,
+                    None => true,
+                };
+                if is_shorter {
+                    all_pairs.distances[a][b] = Some(new_distance);
+                    all_pairs.next_positions[a][b] = all_pairs.next_positions[a][k];
+                }
+            }
+        }
+    }
+
+    Ok(all_pairs)
+}
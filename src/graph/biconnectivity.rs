@@ -0,0 +1,177 @@
+use super::traversal::{DfsEvent, DfsEvents};
+use super::undirected_graph::{Graph, NodeId};
+use serde::de::DeserializeOwned;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Debug;
+
+/// A maximal set of edges which stays connected after removing any one node. Two components
+/// share at most one node, and that node is an articulation point.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct BiconnectedComponent {
+    // In the `NodeId` order
+    pub nodes: Vec<NodeId>,
+
+    // `(a, b)` with `a < b`, in order
+    pub edges: Vec<(NodeId, NodeId)>,
+}
+
+///
+/// # `Biconnectivity`
+///
+/// - `bridges`: the edges which disconnect their component after being removed.
+/// - `articulation_points`: the nodes which disconnect their component after being removed.
+/// - `biconnected_components`: see `BiconnectedComponent`, an isolated node isn't in any of
+///   them since it doesn't have any edge.
+///
+/// Self-loops don't change the connectivity, so they're ignored. Two parallel edges between
+/// the same nodes are never bridges.
+///
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Biconnectivity {
+    // `(a, b)` with `a < b`, in order
+    pub bridges: Vec<(NodeId, NodeId)>,
+
+    // In the `NodeId` order
+    pub articulation_points: Vec<NodeId>,
+
+    // Ordered by their nodes
+    pub biconnected_components: Vec<BiconnectedComponent>,
+}
+
+/// Tarjan's algorithm on top of `DfsEvents`, so it's iterative rather than recursive.
+///
+/// `low[u]` is the smallest discover time reachable from the subtree of `u` by at most one
+/// back edge. It's known when `u` finishes, since all children of `u` have finished by then.
+/// The tree edge `parent -> u` is a bridge if `low[u] > discover[parent]`, and `u` hangs off
+/// `parent` in its own biconnected component if `low[u] >= discover[parent]`.
+pub(crate) fn biconnectivity<T, G>(graph: &G) -> Biconnectivity
+where
+    T: Debug + DeserializeOwned,
+    G: Graph<T>,
+{
+    let mut discover_times = BTreeMap::<NodeId, usize>::new();
+    let mut parents = BTreeMap::<NodeId, Option<NodeId>>::new();
+    let mut low_times = BTreeMap::<NodeId, usize>::new();
+    // All nodes in the discover order (the roots are discovered before their trees)
+    let mut discover_order = Vec::<NodeId>::new();
+
+    for (root, _) in graph.nodes() {
+        if discover_times.contains_key(&root) {
+            continue;
+        }
+
+        for event in DfsEvents::new(graph, root) {
+            match event {
+                DfsEvent::Discover { visit, time } => {
+                    discover_times.insert(visit.node_index, time);
+                    parents.insert(visit.node_index, visit.parent);
+                    discover_order.push(visit.node_index);
+                }
+                DfsEvent::Finish { node_index, .. } => {
+                    let parent = parents[&node_index];
+                    let mut low_time = discover_times[&node_index];
+                    let mut parent_edge_skipped = false;
+                    for (neighbor_index, _, _) in graph.neighbors(node_index) {
+                        if neighbor_index == node_index {
+                            continue;
+                        }
+                        if Some(neighbor_index) == parent && !parent_edge_skipped {
+                            parent_edge_skipped = true;
+                            continue;
+                        }
+
+                        let neighbor_low_time = match parents[&neighbor_index] {
+                            Some(neighbor_parent) if neighbor_parent == node_index => {
+                                low_times[&neighbor_index]
+                            }
+                            _ => discover_times[&neighbor_index],
+                        };
+                        low_time = low_time.min(neighbor_low_time);
+                    }
+                    low_times.insert(node_index, low_time);
+                }
+            }
+        }
+    }
+
+    let mut bridges = vec![];
+    let mut articulation_points = BTreeSet::<NodeId>::new();
+    let mut root_children_counts = BTreeMap::<NodeId, usize>::new();
+    // `child -> component id` of each tree edge `parent -> child`
+    let mut tree_edge_components = BTreeMap::<NodeId, usize>::new();
+    let mut components = Vec::<BTreeSet<(NodeId, NodeId)>>::new();
+
+    for node_index in discover_order.iter().copied() {
+        let parent = match parents[&node_index] {
+            Some(parent) => parent,
+            None => continue,
+        };
+        let (low_time, parent_discover_time) = (low_times[&node_index], discover_times[&parent]);
+
+        if low_time > parent_discover_time {
+            bridges.push((parent.min(node_index), parent.max(node_index)));
+        }
+
+        let component_id = if low_time >= parent_discover_time {
+            match parents[&parent] {
+                Some(_) => {
+                    articulation_points.insert(parent);
+                }
+                None => *root_children_counts.entry(parent).or_insert(0) += 1,
+            }
+            components.push(BTreeSet::new());
+            components.len() - 1
+        } else {
+            // `parent` isn't the root, otherwise `low_time >= parent_discover_time`
+            tree_edge_components[&parent]
+        };
+        tree_edge_components.insert(node_index, component_id);
+        components[component_id].insert((parent.min(node_index), parent.max(node_index)));
+    }
+
+    for (root, children_count) in root_children_counts {
+        if children_count >= 2 {
+            articulation_points.insert(root);
+        }
+    }
+
+    // A back edge belongs to the component of the tree edge above its lower end
+    for node_index in discover_order.iter().copied() {
+        let component_id = match tree_edge_components.get(&node_index) {
+            Some(component_id) => *component_id,
+            None => continue,
+        };
+        for (neighbor_index, _, _) in graph.neighbors(node_index) {
+            if discover_times[&neighbor_index] < discover_times[&node_index]
+                && Some(neighbor_index) != parents[&node_index]
+            {
+                components[component_id].insert((
+                    neighbor_index.min(node_index),
+                    neighbor_index.max(node_index),
+                ));
+            }
+        }
+    }
+
+    let mut biconnected_components = components
+        .into_iter()
+        .map(|edges| {
+            let nodes = edges
+                .iter()
+                .flat_map(|(a, b)| [*a, *b])
+                .collect::<BTreeSet<NodeId>>();
+            BiconnectedComponent {
+                nodes: nodes.into_iter().collect(),
+                edges: edges.into_iter().collect(),
+            }
+        })
+        .collect::<Vec<BiconnectedComponent>>();
+    biconnected_components.sort_unstable_by(|a, b| a.nodes.cmp(&b.nodes));
+    bridges.sort_unstable();
+
+    Biconnectivity {
+        bridges,
+        articulation_points: articulation_points.into_iter().collect(),
+        biconnected_components,
+    }
+}
@@ -0,0 +1,91 @@
+use super::traversal::Bfs;
+use super::undirected_graph::{Graph, NodeId};
+use serde::de::DeserializeOwned;
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+
+///
+/// # `ConnectedComponents`
+///
+/// The component id of each node. The ids are `0..count()`, ordered by the smallest `NodeId`
+/// in each component, so the component of the first node is always `0`.
+///
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ConnectedComponents {
+    // `node_index -> component id`
+    component_ids: BTreeMap<NodeId, usize>,
+
+    // The nodes of each component, in the `NodeId` order
+    components: Vec<Vec<NodeId>>,
+}
+
+///
+impl ConnectedComponents {
+    /// `None` if the node doesn't exist
+    pub fn component_of(&self, node_index: NodeId) -> Option<usize> {
+        self.component_ids.get(&node_index).copied()
+    }
+
+    ///
+    pub fn count(&self) -> usize {
+        self.components.len()
+    }
+
+    /// `None` if there isn't such component
+    pub fn nodes_in(&self, component_id: usize) -> Option<&[NodeId]> {
+        self.components.get(component_id).map(Vec::as_slice)
+    }
+
+    /// The nodes of all components, in the component id order
+    pub fn components(&self) -> impl Iterator<Item = &[NodeId]> {
+        self.components.iter().map(Vec::as_slice)
+    }
+
+    /// All `(node_index, component_id)` in the `NodeId` order
+    pub fn component_ids(&self) -> impl Iterator<Item = (NodeId, usize)> + '_ {
+        self.component_ids
+            .iter()
+            .map(|(node_index, component_id)| (*node_index, *component_id))
+    }
+}
+
+/// Run `Bfs` from each node which isn't in any component yet.
+pub(crate) fn connected_components<T, G>(graph: &G) -> ConnectedComponents
+where
+    T: Debug + DeserializeOwned,
+    G: Graph<T>,
+{
+    let mut connected_components = ConnectedComponents {
+        component_ids: BTreeMap::new(),
+        components: vec![],
+    };
+
+    for (start, _) in graph.nodes() {
+        if connected_components.component_ids.contains_key(&start) {
+            continue;
+        }
+
+        let component_id = connected_components.components.len();
+        let mut nodes = Bfs::new(graph, start)
+            .map(|visit| visit.node_index)
+            .collect::<Vec<NodeId>>();
+        nodes.sort_unstable();
+        for node_index in nodes.iter() {
+            connected_components
+                .component_ids
+                .insert(*node_index, component_id);
+        }
+        connected_components.components.push(nodes);
+    }
+
+    connected_components
+}
+
+/// Whether `b` is reachable from `a`, stop as soon as `b` is visited.
+pub(crate) fn are_connected<T, G>(graph: &G, a: NodeId, b: NodeId) -> bool
+where
+    T: Debug + DeserializeOwned,
+    G: Graph<T>,
+{
+    Bfs::new(graph, a).any(|visit| visit.node_index == b)
+}
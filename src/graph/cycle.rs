@@ -0,0 +1,134 @@
+use super::traversal::{Bfs, DfsEvent, DfsEvents};
+use super::undirected_graph::{Graph, NodeId};
+use serde::de::DeserializeOwned;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Debug;
+
+///
+/// # `Bipartition`
+///
+/// - `TwoSides`: every edge goes between `left` and `right`. In each connected component, the
+///   smallest `NodeId` is on the `left`.
+/// - `OddCycle`: a cycle with an odd number of edges, which proves there isn't any bipartition.
+///   The last node connects back to the first one, a self-loop is a cycle of one node.
+///
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Bipartition {
+    TwoSides {
+        left: Vec<NodeId>,
+        right: Vec<NodeId>,
+    },
+    OddCycle(Vec<NodeId>),
+}
+
+/// Depth-first search for a back edge (to a node on the current DFS path), the first one found
+/// gives back the cycle from that node down to the current node. The last node connects back
+/// to the first one. A self-loop is a cycle of one node, two parallel edges are a cycle of two
+/// nodes.
+pub(crate) fn find_cycle<T, G>(graph: &G) -> Option<Vec<NodeId>>
+where
+    T: Debug + DeserializeOwned,
+    G: Graph<T>,
+{
+    let mut parents = BTreeMap::<NodeId, Option<NodeId>>::new();
+    // The nodes which are discovered but not finished yet
+    let mut on_path = BTreeSet::<NodeId>::new();
+
+    for (root, _) in graph.nodes() {
+        if parents.contains_key(&root) {
+            continue;
+        }
+
+        for event in DfsEvents::new(graph, root) {
+            let visit = match event {
+                DfsEvent::Discover { visit, .. } => visit,
+                DfsEvent::Finish { node_index, .. } => {
+                    on_path.remove(&node_index);
+                    continue;
+                }
+            };
+            parents.insert(visit.node_index, visit.parent);
+            on_path.insert(visit.node_index);
+
+            let mut parent_edge_skipped = false;
+            for (neighbor_index, _, _) in graph.neighbors(visit.node_index) {
+                if Some(neighbor_index) == visit.parent && !parent_edge_skipped {
+                    parent_edge_skipped = true;
+                    continue;
+                }
+                if !on_path.contains(&neighbor_index) {
+                    continue;
+                }
+
+                let mut cycle = vec![visit.node_index];
+                let mut current_node = visit.node_index;
+                while current_node != neighbor_index {
+                    current_node = parents[&current_node].unwrap();
+                    cycle.push(current_node);
+                }
+                cycle.reverse();
+                return Some(cycle);
+            }
+        }
+    }
+
+    None
+}
+
+/// Color each connected component by the `Bfs` depth, the even ones go `left` and the odd ones
+/// go `right`. Then an edge between two nodes with the same color closes an odd cycle through
+/// their lowest common ancestor on the `Bfs` tree.
+pub(crate) fn bipartition<T, G>(graph: &G) -> Bipartition
+where
+    T: Debug + DeserializeOwned,
+    G: Graph<T>,
+{
+    // `node_index -> (depth, parent)` on the `Bfs` tree
+    let mut visits = BTreeMap::<NodeId, (usize, Option<NodeId>)>::new();
+    for (root, _) in graph.nodes() {
+        if visits.contains_key(&root) {
+            continue;
+        }
+        for visit in Bfs::new(graph, root) {
+            visits.insert(visit.node_index, (visit.depth, visit.parent));
+        }
+    }
+
+    for (node_index, (depth, _)) in visits.iter() {
+        for (neighbor_index, _, _) in graph.neighbors(*node_index) {
+            if visits[&neighbor_index].0 % 2 != depth % 2 {
+                continue;
+            }
+
+            // Both nodes have the same depth, since the depths of two neighbors differ by at
+            // most one. So they reach the common ancestor at the same time.
+            let (mut a, mut b) = (*node_index, neighbor_index);
+            let (mut a_side, mut b_side) = (vec![], vec![]);
+            while a != b {
+                a_side.push(a);
+                b_side.push(b);
+                a = visits[&a].1.unwrap();
+                b = visits[&b].1.unwrap();
+            }
+
+            let mut cycle = vec![a];
+            cycle.extend(a_side.into_iter().rev());
+            cycle.extend(b_side);
+            return Bipartition::OddCycle(cycle);
+        }
+    }
+
+    let (left, right) = visits
+        .iter()
+        .partition::<Vec<_>, _>(|(_, (depth, _))| depth % 2 == 0);
+    Bipartition::TwoSides {
+        left: left
+            .into_iter()
+            .map(|(node_index, _)| *node_index)
+            .collect(),
+        right: right
+            .into_iter()
+            .map(|(node_index, _)| *node_index)
+            .collect(),
+    }
+}
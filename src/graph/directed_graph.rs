@@ -0,0 +1,537 @@
+use super::all_pairs_shortest_path::{floyd_warshall, AllPairsShortestPaths};
+use super::filtered_search::NoFilter;
+use super::graph_file::read_graph_file;
+use super::graph_load_error::GraphLoadError;
+use super::max_flow::{edmonds_karp, MaxFlow};
+use super::shortest_path::{
+    a_star, bellman_ford, dijkstra, find_negative_cycle, AStarResult, NegativeCycle, ShortestPath,
+    ShortestPathTree,
+};
+use super::topological::{
+    condensation, strongly_connected_components, topological_sort, DirectedCycle,
+    StronglyConnectedComponents,
+};
+use super::traversal::{Bfs, Dfs, DfsEvents};
+use super::undirected_graph::{
+    remove_nodes_without_data, Graph, GraphNode, GraphNodeEdge, NodeId, UndirectedGraph,
+};
+use super::weight::Weight;
+use serde::de::DeserializeOwned;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Debug;
+use std::ops::Sub;
+
+///
+/// # `DirectedGraph`
+///
+/// Each edge `A -> B` only goes from `A` to `B`, it's saved as an out-edge in `A` (the
+/// `neighbors` of `A`) and an in-edge in `B`. It loads the same file format as
+/// `UndirectedGraph`, but the edges don't need to have the edge back.
+///
+/// `neighbors`, `degree`, `has_edge` and `edge_weight` of the `Graph` trait only look at the
+/// out-edges, so all algorithms on `Graph` follow the edge direction.
+///
+/// # Index stability
+///
+/// Same as `UndirectedGraph`, a removed `NodeId` is never reused, and converting between
+/// `DirectedGraph` and `UndirectedGraph` keeps all `NodeId`.
+///
+pub struct DirectedGraph<T: Debug + DeserializeOwned, W: Weight = usize> {
+    // The `neighbors` of each node are its out-edges
+    nodes: Vec<GraphNode<T, W>>,
+
+    // The in-edges of each node, `incoming[b]` has `a` for each edge `a -> b`
+    incoming: Vec<Vec<GraphNodeEdge<W>>>,
+
+    // Tombstones of the removed nodes
+    removed_nodes: BTreeSet<NodeId>,
+}
+
+impl<T: Debug + DeserializeOwned, W: Weight> DirectedGraph<T, W> {
+    /// Build the in-edges from the out-edges, the edges to a node out of `nodes` are skipped.
+    fn from_parts(nodes: Vec<GraphNode<T, W>>, removed_nodes: BTreeSet<NodeId>) -> Self {
+        let mut incoming = (0..nodes.len()).map(|_| vec![]).collect::<Vec<_>>();
+        for (from, node) in nodes.iter().enumerate() {
+            for edge in node.neighbors.iter() {
+                if let Some(in_edges) = incoming.get_mut(edge.node_index) {
+                    in_edges.push(GraphNodeEdge {
+                        node_index: from,
+                        weight: edge.weight,
+                    });
+                }
+            }
+        }
+
+        DirectedGraph {
+            nodes,
+            incoming,
+            removed_nodes,
+        }
+    }
+
+    /// All `(neighbor_index, weight, neighbor_data)` of the edges from the node, same as
+    /// `neighbors`
+    pub fn out_neighbors(&self, node_index: NodeId) -> impl Iterator<Item = (NodeId, W, &T)> + '_ {
+        self.neighbors(node_index)
+    }
+
+    /// All `(neighbor_index, weight, neighbor_data)` of the edges to the node, it's empty if
+    /// the node doesn't exist.
+    pub fn in_neighbors(&self, node_index: NodeId) -> impl Iterator<Item = (NodeId, W, &T)> + '_ {
+        let edges = if self.contains_node(node_index) {
+            &self.incoming[node_index][..]
+        } else {
+            &[]
+        };
+
+        edges.iter().filter_map(move |edge| {
+            self.node(edge.node_index)
+                .map(|data| (edge.node_index, edge.weight, data))
+        })
+    }
+
+    /// How many edges go from the node, `None` if the node doesn't exist.
+    pub fn out_degree(&self, node_index: NodeId) -> Option<usize> {
+        self.degree(node_index)
+    }
+
+    /// How many edges go to the node, `None` if the node doesn't exist.
+    pub fn in_degree(&self, node_index: NodeId) -> Option<usize> {
+        if !self.contains_node(node_index) {
+            return None;
+        }
+
+        Some(self.incoming[node_index].len())
+    }
+
+    /// The same graph with all edges reversed, `A -> B` becomes `B -> A`.
+    pub fn into_reversed(mut self) -> Self {
+        for (node, in_edges) in self.nodes.iter_mut().zip(self.incoming.iter_mut()) {
+            std::mem::swap(&mut node.neighbors, in_edges);
+        }
+
+        self
+    }
+
+    /// Same as `into_reversed`, but keeps the current graph.
+    pub fn reversed(&self) -> Self
+    where
+        T: Clone,
+    {
+        let nodes = self
+            .nodes
+            .iter()
+            .zip(self.incoming.iter())
+            .map(|(node, in_edges)| GraphNode {
+                data: node.data.clone(),
+                neighbors: in_edges
+                    .iter()
+                    .map(|edge| GraphNodeEdge {
+                        node_index: edge.node_index,
+                        weight: edge.weight,
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        Self::from_parts(nodes, self.removed_nodes.clone())
+    }
+
+    /// Breadth-first traversal from `start` along the edge direction, see `Bfs`
+    pub fn bfs(&self, start: NodeId) -> Bfs<'_, T, Self> {
+        Bfs::new(self, start)
+    }
+
+    /// Depth-first traversal from `start` along the edge direction in pre-order, see `Dfs`
+    pub fn dfs(&self, start: NodeId) -> Dfs<'_, T, Self> {
+        Dfs::new(self, start)
+    }
+
+    /// Depth-first traversal from `start` along the edge direction with both pre-order and
+    /// post-order events, see `DfsEvents`
+    pub fn dfs_events(&self, start: NodeId) -> DfsEvents<'_, T, Self> {
+        DfsEvents::new(self, start)
+    }
+
+    /// The path with the smallest total weight from `from` to `to` along the edge direction
+    /// (Dijkstra's algorithm), `None` if `to` isn't reachable or any of them doesn't exist.
+    pub fn shortest_path(&self, from: NodeId, to: NodeId) -> Option<ShortestPath<W>> {
+        dijkstra(self, from, Some(to), &mut NoFilter).path_to(to)
+    }
+
+    /// The shortest distances (and paths) from `from` to all reachable nodes
+    pub fn shortest_path_tree(&self, from: NodeId) -> ShortestPathTree<W> {
+        dijkstra(self, from, None, &mut NoFilter)
+    }
+
+    /// Same as `shortest_path_tree`, but it works with the negative weights (Bellman-Ford
+    /// algorithm). Fails if any negative cycle is reachable from `from`.
+    pub fn bellman_ford_shortest_path_tree(
+        &self,
+        from: NodeId,
+    ) -> Result<ShortestPathTree<W>, NegativeCycle> {
+        bellman_ford(self, from)
+    }
+
+    /// The shortest distances (and paths) between every two nodes along the edge direction by
+    /// Floyd-Warshall algorithm, see `AllPairsShortestPaths`. Fails if there is any negative
+    /// cycle.
+    pub fn floyd_warshall_all_pairs(&self) -> Result<AllPairsShortestPaths<W>, NegativeCycle> {
+        floyd_warshall(self)
+    }
+
+    /// Any cycle with a negative total weight, no matter which nodes can reach it.
+    pub fn find_negative_cycle(&self) -> Option<NegativeCycle> {
+        find_negative_cycle(self)
+    }
+
+    /// A* search from `from` to `to` along the edge direction, see `UndirectedGraph::a_star`
+    pub fn a_star<H>(&self, from: NodeId, to: NodeId, heuristic: H) -> AStarResult<W>
+    where
+        H: FnMut(&T, &T) -> W,
+    {
+        a_star(self, from, to, heuristic, &mut NoFilter)
+    }
+
+    /// The maximum flow from `source` to `sink` along the edge direction (Edmonds-Karp
+    /// algorithm) and the minimum cut, each edge weight is its capacity. See `MaxFlow`, `None`
+    /// if any of them doesn't exist or `source == sink`.
+    ///
+    /// ```
+    /// # use data_structure_implementation_by_rust::graph::directed_graph::DirectedGraph;
+    /// # use data_structure_implementation_by_rust::graph::undirected_graph::Graph;
+    /// # let mut routes = DirectedGraph::<String>::with_all_nodes(vec![]);
+    /// # let home = routes.add_node("Alien Home".to_string());
+    /// # let mars = routes.add_node("Mars".to_string());
+    /// # routes.add_edge(home, mars, 5).unwrap();
+    /// let flow = routes.max_flow(home, mars).unwrap();
+    /// println!("ship {} per day, the bottlenecks are {:?}", flow.value, flow.cut_edges);
+    /// # assert_eq!(flow.value, 5);
+    /// ```
+    pub fn max_flow(&self, source: NodeId, sink: NodeId) -> Option<MaxFlow<W>>
+    where
+        W: Sub<Output = W>,
+    {
+        edmonds_karp(self, source, sink)
+    }
+
+    /// All nodes ordered so that every edge goes from an earlier node to a later one (Kahn's
+    /// algorithm), or a cycle if there isn't such order.
+    ///
+    /// ```
+    /// # use data_structure_implementation_by_rust::graph::directed_graph::DirectedGraph;
+    /// # use data_structure_implementation_by_rust::graph::undirected_graph::Graph;
+    /// # let mut quests = DirectedGraph::<String>::with_all_nodes(vec![]);
+    /// # let find_map = quests.add_node("Find the map".to_string());
+    /// # let reach_mars = quests.add_node("Reach Mars".to_string());
+    /// # quests.add_edge(find_map, reach_mars, 1).unwrap();
+    /// match quests.topological_sort() {
+    ///     Ok(order) => println!("play in order: {order:?}"),
+    ///     Err(cycle) => println!("quests depend on each other: {:?}", cycle.nodes),
+    /// }
+    /// # assert_eq!(quests.topological_sort(), Ok(vec![find_map, reach_mars]));
+    /// ```
+    pub fn topological_sort(&self) -> Result<Vec<NodeId>, DirectedCycle> {
+        topological_sort(self)
+    }
+
+    /// The component id of each node, see `StronglyConnectedComponents`
+    pub fn strongly_connected_components(&self) -> StronglyConnectedComponents {
+        strongly_connected_components(self)
+    }
+
+    /// The condensation DAG, each node is a strongly connected component with the same id as
+    /// in `strongly_connected_components`, and its data is the nodes in that component.
+    pub fn condensation(&self) -> DirectedGraph<Vec<NodeId>, W> {
+        condensation(self, &strongly_connected_components(self))
+    }
+
+    /// Whether the node exists and it's not removed
+    pub fn contains_node(&self, node_index: NodeId) -> bool {
+        node_index < self.nodes.len() && !self.removed_nodes.contains(&node_index)
+    }
+
+    /// The position of the first edge `from -> to` in `nodes[from].neighbors`
+    fn edge_position(&self, from: NodeId, to: NodeId) -> Option<usize> {
+        if !self.contains_node(from) {
+            return None;
+        }
+
+        self.nodes[from]
+            .neighbors
+            .iter()
+            .position(|edge| edge.node_index == to)
+    }
+
+    /// The position of the in-edge which matches the out-edge `from -> to` (with `weight`) in
+    /// `incoming[to]`
+    fn in_edge_position(&self, from: NodeId, to: NodeId, weight: W) -> Option<usize> {
+        self.incoming
+            .get(to)?
+            .iter()
+            .position(|edge| edge.node_index == from && edge.weight == weight)
+    }
+}
+
+impl<T: Debug + DeserializeOwned, W: Weight> std::fmt::Debug for DirectedGraph<T, W> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let mut debug_info = f.debug_struct("[ DirectedGraph ]");
+        debug_info.field("nodes_len", &self.nodes_len());
+        debug_info.field("edges_len", &self.edges_len());
+
+        //
+        // Output like a adjacency list, only with the out-edges
+        //
+        let live_nodes = self
+            .nodes
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| !self.removed_nodes.contains(index));
+        for (position, (index, node)) in live_nodes.enumerate() {
+            let label = if position == 0 {
+                format!(
+                    "\n>>> Nodes in adjacency list format>>>\n\n[{}] {:?}",
+                    index, &node.data
+                )
+            } else {
+                format!("[{}] {:?}", index, &node.data)
+            };
+
+            let out_edges = node
+                .neighbors
+                .iter()
+                .map(|edge| format!("-> {:?}({})", self.nodes[edge.node_index].data, edge.weight))
+                .collect::<Vec<String>>();
+            debug_info.field(&label, &out_edges.join(", "));
+        }
+
+        debug_info.finish()
+    }
+}
+
+impl<T: Debug + DeserializeOwned, W: Weight> Graph<T> for DirectedGraph<T, W> {
+    type Weight = W;
+
+    ///
+    fn with_first_node(first_node: GraphNode<T, W>) -> Self {
+        Self::with_all_nodes(vec![first_node])
+    }
+
+    /// The `neighbors` of each node are its out-edges
+    fn with_all_nodes(mut nodes: Vec<GraphNode<T, W>>) -> Self {
+        let removed_nodes = remove_nodes_without_data(&mut nodes);
+        Self::from_parts(nodes, removed_nodes)
+    }
+
+    /// Each `-> index(weight)` is only one edge from the current line's node, so there isn't any
+    /// symmetry check.
+    fn load_from_file(graph_filename: &str) -> Result<Self, GraphLoadError<W>>
+    where
+        Self: Sized,
+    {
+        let (nodes, _) = read_graph_file::<T, W>(graph_filename)?;
+        Ok(Self::with_all_nodes(nodes))
+    }
+
+    ///
+    fn add_node(&mut self, data: T) -> NodeId {
+        self.nodes.push(GraphNode {
+            data: Some(data),
+            neighbors: vec![],
+        });
+        self.incoming.push(vec![]);
+        self.nodes.len() - 1
+    }
+
+    /// Add the edge `a -> b`. Fails if any of them doesn't exist, or `a -> b` is already there
+    /// (`b -> a` doesn't matter).
+    fn add_edge(&mut self, a: NodeId, b: NodeId, weight: W) -> Result<(), String> {
+        for node_index in [a, b] {
+            if !self.contains_node(node_index) {
+                return Err(format!("Node {} doesn't exist", node_index));
+            }
+        }
+
+        if self.edge_position(a, b).is_some() {
+            return Err(format!("Node {} is already connected to {}", a, b));
+        }
+
+        self.nodes[a].neighbors.push(GraphNodeEdge {
+            node_index: b,
+            weight,
+        });
+        self.incoming[b].push(GraphNodeEdge {
+            node_index: a,
+            weight,
+        });
+
+        Ok(())
+    }
+
+    /// Remove the edge `a -> b`, the edge `b -> a` (if any) stays.
+    fn remove_edge(&mut self, a: NodeId, b: NodeId) -> Option<W> {
+        let position = self.edge_position(a, b)?;
+        let removed_edge = self.nodes[a].neighbors.remove(position);
+        if let Some(in_position) = self.in_edge_position(a, b, removed_edge.weight) {
+            self.incoming[b].remove(in_position);
+        }
+
+        Some(removed_edge.weight)
+    }
+
+    /// Remove the node and all edges from or to it
+    fn remove_node(&mut self, node_index: NodeId) -> Option<T> {
+        if !self.contains_node(node_index) {
+            return None;
+        }
+
+        let out_edges = std::mem::take(&mut self.nodes[node_index].neighbors);
+        let in_edges = std::mem::take(&mut self.incoming[node_index]);
+        for edge in out_edges {
+            self.incoming[edge.node_index].retain(|edge| edge.node_index != node_index);
+        }
+        for edge in in_edges {
+            self.nodes[edge.node_index]
+                .neighbors
+                .retain(|edge| edge.node_index != node_index);
+        }
+
+        self.removed_nodes.insert(node_index);
+        self.nodes[node_index].data.take()
+    }
+
+    /// Change the weight of the edge `a -> b`, get back the old weight.
+    fn update_weight(&mut self, a: NodeId, b: NodeId, weight: W) -> Option<W> {
+        let position = self.edge_position(a, b)?;
+        let old_weight = std::mem::replace(&mut self.nodes[a].neighbors[position].weight, weight);
+        if let Some(in_position) = self.in_edge_position(a, b, old_weight) {
+            self.incoming[b][in_position].weight = weight;
+        }
+
+        Some(old_weight)
+    }
+
+    ///
+    fn node_data_mut(&mut self, node_index: NodeId) -> Option<&mut T> {
+        if !self.contains_node(node_index) {
+            return None;
+        }
+
+        self.nodes[node_index].data.as_mut()
+    }
+
+    ///
+    fn nodes_len(&self) -> usize {
+        self.nodes.len() - self.removed_nodes.len()
+    }
+
+    /// Each edge is only counted once
+    fn edges_len(&self) -> usize {
+        self.nodes
+            .iter()
+            .fold(0, |acc, ele| acc + ele.neighbors.len())
+    }
+
+    ///
+    fn node(&self, node_index: NodeId) -> Option<&T> {
+        if !self.contains_node(node_index) {
+            return None;
+        }
+
+        self.nodes[node_index].data.as_ref()
+    }
+
+    /// The out-neighbors, see `in_neighbors` for the other direction
+    fn neighbors<'a>(&'a self, node_index: NodeId) -> impl Iterator<Item = (NodeId, W, &'a T)>
+    where
+        T: 'a,
+    {
+        let edges = if self.contains_node(node_index) {
+            &self.nodes[node_index].neighbors[..]
+        } else {
+            &[]
+        };
+
+        edges.iter().filter_map(move |edge| {
+            self.node(edge.node_index)
+                .map(|data| (edge.node_index, edge.weight, data))
+        })
+    }
+
+    /// The out-degree, see `in_degree` for the other direction
+    fn degree(&self, node_index: NodeId) -> Option<usize> {
+        if !self.contains_node(node_index) {
+            return None;
+        }
+
+        Some(self.nodes[node_index].neighbors.len())
+    }
+
+    /// Whether there is the edge `a -> b`
+    fn has_edge(&self, a: NodeId, b: NodeId) -> bool {
+        self.edge_position(a, b).is_some()
+    }
+
+    /// The weight of the edge `a -> b`
+    fn edge_weight(&self, a: NodeId, b: NodeId) -> Option<W> {
+        self.edge_position(a, b)
+            .map(|position| self.nodes[a].neighbors[position].weight)
+    }
+
+    ///
+    fn nodes<'a>(&'a self) -> impl Iterator<Item = (NodeId, &'a T)>
+    where
+        T: 'a,
+    {
+        (0..self.nodes.len())
+            .filter_map(move |node_index| self.node(node_index).map(|data| (node_index, data)))
+    }
+}
+
+/// Each undirected edge becomes two edges in both directions (a self-loop becomes one edge).
+impl<T: Debug + DeserializeOwned, W: Weight> From<UndirectedGraph<T, W>> for DirectedGraph<T, W> {
+    fn from(graph: UndirectedGraph<T, W>) -> Self {
+        let (nodes, removed_nodes) = graph.into_parts();
+        Self::from_parts(nodes, removed_nodes)
+    }
+}
+
+/// Symmetrize the edges: `A -> B`, `B -> A` or both become one undirected edge between `A` and
+/// `B`. If there are more than one of them, the smallest weight wins. The neighbors of each
+/// node are in the `NodeId` order.
+impl<T: Debug + DeserializeOwned, W: Weight> From<DirectedGraph<T, W>> for UndirectedGraph<T, W> {
+    fn from(graph: DirectedGraph<T, W>) -> Self {
+        let DirectedGraph {
+            mut nodes,
+            removed_nodes,
+            ..
+        } = graph;
+
+        // `(smaller_node, bigger_node) -> weight`
+        let mut edges = BTreeMap::<(NodeId, NodeId), W>::new();
+        for (from, node) in nodes.iter_mut().enumerate() {
+            for edge in std::mem::take(&mut node.neighbors) {
+                let node_pair = (from.min(edge.node_index), from.max(edge.node_index));
+                let weight = edges.entry(node_pair).or_insert(edge.weight);
+                if edge.weight < *weight {
+                    *weight = edge.weight;
+                }
+            }
+        }
+
+        for ((a, b), weight) in edges {
+            nodes[a].neighbors.push(GraphNodeEdge {
+                node_index: b,
+                weight,
+            });
+            if a != b {
+                nodes[b].neighbors.push(GraphNodeEdge {
+                    node_index: a,
+                    weight,
+                });
+            }
+        }
+
+        UndirectedGraph::from_parts(nodes, removed_nodes)
+    }
+}
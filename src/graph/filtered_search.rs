@@ -0,0 +1,261 @@
+use super::connectivity::are_connected;
+use super::shortest_path::{a_star, dijkstra, ShortestPath};
+use super::undirected_graph::{Graph, NodeId};
+use super::weight::Weight;
+use crate::queue::Queue;
+use serde::de::DeserializeOwned;
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+
+///
+/// # `PathSearchOutcome`
+///
+/// The result of a filtered path search:
+///
+/// - `Found`: the path only goes through the allowed nodes and edges.
+/// - `Blocked`: there is a path from `from` to `to`, but all of them go through a node or an
+///   edge which is filtered out (including `from` or `to` itself).
+/// - `NoPath`: `to` isn't reachable even without any filter, or any of them doesn't exist.
+///
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum PathSearchOutcome<W = usize> {
+    Found(ShortestPath<W>),
+    Blocked,
+    NoPath,
+}
+
+///
+impl<W> PathSearchOutcome<W> {
+    /// `None` if it's `Blocked` or `NoPath`
+    pub fn path(&self) -> Option<&ShortestPath<W>> {
+        match self {
+            PathSearchOutcome::Found(path) => Some(path),
+            _ => None,
+        }
+    }
+}
+
+/// Same as `AStarResult`, but with the filtered `outcome`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct FilteredAStarResult<W = usize> {
+    pub outcome: PathSearchOutcome<W>,
+    pub nodes_expanded: usize,
+}
+
+/// Decides which nodes and edges a search can go through.
+pub(crate) trait SearchFilter<T, W> {
+    fn allows_node(&mut self, node_index: NodeId, data: &T) -> bool;
+    fn allows_edge(&mut self, from: NodeId, to: NodeId, weight: W) -> bool;
+}
+
+/// Everything is allowed, for the unfiltered searches.
+pub(crate) struct NoFilter;
+
+///
+impl<T, W> SearchFilter<T, W> for NoFilter {
+    fn allows_node(&mut self, _: NodeId, _: &T) -> bool {
+        true
+    }
+
+    fn allows_edge(&mut self, _: NodeId, _: NodeId, _: W) -> bool {
+        true
+    }
+}
+
+/// The user predicates, and whether any node or edge has been filtered out by them. If nothing
+/// is filtered out, a failed search is a `NoPath` without checking again.
+struct PredicateFilter<N, E> {
+    node_predicate: N,
+    edge_predicate: E,
+    rejected: bool,
+}
+
+///
+impl<T, W, N, E> SearchFilter<T, W> for PredicateFilter<N, E>
+where
+    N: FnMut(NodeId, &T) -> bool,
+    E: FnMut(NodeId, NodeId, W) -> bool,
+{
+    fn allows_node(&mut self, node_index: NodeId, data: &T) -> bool {
+        let allowed = (self.node_predicate)(node_index, data);
+        self.rejected |= !allowed;
+        allowed
+    }
+
+    fn allows_edge(&mut self, from: NodeId, to: NodeId, weight: W) -> bool {
+        let allowed = (self.edge_predicate)(from, to, weight);
+        self.rejected |= !allowed;
+        allowed
+    }
+}
+
+/// Check `from` and `to` before searching, `Err` is the final outcome.
+fn check_ends<T, G, F>(
+    graph: &G,
+    from: NodeId,
+    to: NodeId,
+    filter: &mut F,
+) -> Result<(), PathSearchOutcome<G::Weight>>
+where
+    T: Debug + DeserializeOwned,
+    G: Graph<T>,
+    F: SearchFilter<T, G::Weight>,
+{
+    let (from_data, to_data) = match (graph.node(from), graph.node(to)) {
+        (Some(from_data), Some(to_data)) => (from_data, to_data),
+        _ => return Err(PathSearchOutcome::NoPath),
+    };
+
+    if !filter.allows_node(from, from_data) || !filter.allows_node(to, to_data) {
+        return Err(match are_connected(graph, from, to) {
+            true => PathSearchOutcome::Blocked,
+            false => PathSearchOutcome::NoPath,
+        });
+    }
+
+    Ok(())
+}
+
+/// Tell `Blocked` from `NoPath` when the filtered search doesn't find any path.
+fn outcome_of<T, G, N, E>(
+    graph: &G,
+    from: NodeId,
+    to: NodeId,
+    path: Option<ShortestPath<G::Weight>>,
+    filter: &PredicateFilter<N, E>,
+) -> PathSearchOutcome<G::Weight>
+where
+    T: Debug + DeserializeOwned,
+    G: Graph<T>,
+{
+    match path {
+        Some(path) => PathSearchOutcome::Found(path),
+        None if filter.rejected && are_connected(graph, from, to) => PathSearchOutcome::Blocked,
+        None => PathSearchOutcome::NoPath,
+    }
+}
+
+/// Breadth-first search for the path with the fewest edges, only through the nodes and edges
+/// allowed by the predicates. The `cost` is the total weight of that path (saturated, see
+/// `Weight::saturating_add`), it's not always the smallest one.
+pub(crate) fn filtered_bfs_path<T, G, N, E>(
+    graph: &G,
+    from: NodeId,
+    to: NodeId,
+    node_predicate: N,
+    edge_predicate: E,
+) -> PathSearchOutcome<G::Weight>
+where
+    T: Debug + DeserializeOwned,
+    G: Graph<T>,
+    N: FnMut(NodeId, &T) -> bool,
+    E: FnMut(NodeId, NodeId, G::Weight) -> bool,
+{
+    let mut filter = PredicateFilter {
+        node_predicate,
+        edge_predicate,
+        rejected: false,
+    };
+    if let Err(outcome) = check_ends(graph, from, to, &mut filter) {
+        return outcome;
+    }
+
+    // `node_index -> (parent, edge weight from the parent)`
+    let mut discovered = BTreeMap::<NodeId, Option<(NodeId, G::Weight)>>::new();
+    let mut queue = Queue::new();
+    discovered.insert(from, None);
+    queue.enqueue(from);
+
+    let mut path = None;
+    while let Some(node_index) = queue.dequeue() {
+        if node_index == to {
+            let mut nodes = vec![to];
+            let mut cost = G::Weight::zero();
+            let mut current_node = to;
+            while let Some((parent, weight)) = discovered[&current_node] {
+                nodes.push(parent);
+                cost = cost.saturating_add(weight);
+                current_node = parent;
+            }
+            nodes.reverse();
+            path = Some(ShortestPath { cost, nodes });
+            break;
+        }
+
+        for (neighbor_index, weight, neighbor_data) in graph.neighbors(node_index) {
+            if discovered.contains_key(&neighbor_index)
+                || !filter.allows_edge(node_index, neighbor_index, weight)
+                || !filter.allows_node(neighbor_index, neighbor_data)
+            {
+                continue;
+            }
+
+            discovered.insert(neighbor_index, Some((node_index, weight)));
+            queue.enqueue(neighbor_index);
+        }
+    }
+
+    outcome_of(graph, from, to, path, &filter)
+}
+
+/// Same as `dijkstra`, but only through the nodes and edges allowed by the predicates.
+pub(crate) fn filtered_dijkstra<T, G, N, E>(
+    graph: &G,
+    from: NodeId,
+    to: NodeId,
+    node_predicate: N,
+    edge_predicate: E,
+) -> PathSearchOutcome<G::Weight>
+where
+    T: Debug + DeserializeOwned,
+    G: Graph<T>,
+    N: FnMut(NodeId, &T) -> bool,
+    E: FnMut(NodeId, NodeId, G::Weight) -> bool,
+{
+    let mut filter = PredicateFilter {
+        node_predicate,
+        edge_predicate,
+        rejected: false,
+    };
+    if let Err(outcome) = check_ends(graph, from, to, &mut filter) {
+        return outcome;
+    }
+
+    let path = dijkstra(graph, from, Some(to), &mut filter).path_to(to);
+    outcome_of(graph, from, to, path, &filter)
+}
+
+/// Same as `a_star`, but only through the nodes and edges allowed by the predicates.
+pub(crate) fn filtered_a_star<T, G, H, N, E>(
+    graph: &G,
+    from: NodeId,
+    to: NodeId,
+    heuristic: H,
+    node_predicate: N,
+    edge_predicate: E,
+) -> FilteredAStarResult<G::Weight>
+where
+    T: Debug + DeserializeOwned,
+    G: Graph<T>,
+    H: FnMut(&T, &T) -> G::Weight,
+    N: FnMut(NodeId, &T) -> bool,
+    E: FnMut(NodeId, NodeId, G::Weight) -> bool,
+{
+    let mut filter = PredicateFilter {
+        node_predicate,
+        edge_predicate,
+        rejected: false,
+    };
+    if let Err(outcome) = check_ends(graph, from, to, &mut filter) {
+        return FilteredAStarResult {
+            outcome,
+            nodes_expanded: 0,
+        };
+    }
+
+    let result = a_star(graph, from, to, heuristic, &mut filter);
+    FilteredAStarResult {
+        outcome: outcome_of(graph, from, to, result.path, &filter),
+        nodes_expanded: result.nodes_expanded,
+    }
+}
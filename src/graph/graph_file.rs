@@ -0,0 +1,175 @@
+use super::graph_load_error::GraphLoadError;
+use super::undirected_graph::{Graph, GraphNode, GraphNodeEdge, NodeId};
+use super::weight::Weight;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{from_str, to_string};
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::fs::File;
+use std::io::{self, BufRead, Write};
+
+/// All nodes in the graph file, and `(line_number, column_number)` of each edge
+type GraphFileContent<T, W> = (Vec<GraphNode<T, W>>, Vec<(usize, usize)>);
+
+/// Read all nodes and their edges from the graph file (see `Graph::load_from_file` for the
+/// format), the edges are kept as they are in the file. It's shared by all graph types, each
+/// of them checks the edges in its own way after reading.
+///
+/// Besides the nodes, it also gives back `(line_number, column_number)` of each edge, in the
+/// same order as all nodes' `neighbors`. All edges connect to an existing node.
+pub(crate) fn read_graph_file<T: Debug + DeserializeOwned, W: Weight>(
+    graph_filename: &str,
+) -> Result<GraphFileContent<T, W>, GraphLoadError<W>> {
+    let file = File::open(graph_filename).map_err(|error| GraphLoadError::Io {
+        line_number: None,
+        error,
+    })?;
+
+    let mut nodes = Vec::<GraphNode<T, W>>::new();
+
+    // `(line_number, column_number)` of each edge, in the same order as all neighbors, so
+    // the dangling edges can be reported after all nodes are loaded.
+    let mut edge_positions = Vec::<(usize, usize)>::new();
+
+    for (line_index, node_str) in io::BufReader::new(file).lines().enumerate() {
+        let line_number = line_index + 1;
+        let node_str = node_str.map_err(|error| GraphLoadError::Io {
+            line_number: Some(line_number),
+            error,
+        })?;
+
+        if node_str.trim().is_empty() {
+            continue;
+        }
+
+        //
+        // Each line has the following format:
+        //
+        // `{...JSON data here} | edges_separated_by_comma`
+        //
+        // Each edge has the following format:
+        //
+        // `-> Connected_node_index(edge_weight)`
+        //
+        // Here is the example:
+        //
+        // {"name": "Alien Home"} |  -> 1(5),
+        // {"name": "Earth"} | -> 0(5), -> 2(8),
+        // {"name": "Mars"} | -> 1(8),
+        //
+        // Edges never have `|`, so the last `|` is the separator even if the JSON has one.
+        let separator_pos =
+            node_str
+                .rfind('|')
+                .ok_or_else(|| GraphLoadError::MissingSeparator {
+                    line_number,
+                    column_number: column_number_of(&node_str, node_str.len()),
+                })?;
+
+        // GraphNode data json
+        let data_str = &node_str[..separator_pos];
+        let data = from_str::<T>(data_str).map_err(|error| GraphLoadError::InvalidJson {
+            line_number,
+            column_number: column_number_of(data_str, error.column().saturating_sub(1)),
+            message: error.to_string(),
+        })?;
+
+        let mut graph_node = GraphNode::<T, W> {
+            data: Some(data),
+            neighbors: vec![],
+        };
+
+        // GraphNodeEdge
+        let mut edge_start = separator_pos + 1;
+        for edge_str in node_str[separator_pos + 1..].split(',') {
+            let edge_byte_index = edge_start + edge_str.len() - edge_str.trim_start().len();
+            edge_start += edge_str.len() + 1;
+
+            if edge_str.trim() == "" {
+                continue;
+            }
+
+            let column_number = column_number_of(&node_str, edge_byte_index);
+            let (node_index, weight) =
+                parse_edge(edge_str).ok_or_else(|| GraphLoadError::InvalidEdge {
+                    line_number,
+                    column_number,
+                    edge: edge_str.trim().to_string(),
+                })?;
+
+            graph_node
+                .neighbors
+                .push(GraphNodeEdge { node_index, weight });
+            edge_positions.push((line_number, column_number));
+        }
+
+        nodes.push(graph_node);
+    }
+
+    let nodes_len = nodes.len();
+    let all_edges = nodes.iter().flat_map(|node| node.neighbors.iter());
+    for (edge, (line_number, column_number)) in all_edges.zip(edge_positions.iter().copied()) {
+        if edge.node_index >= nodes_len {
+            return Err(GraphLoadError::DanglingEdge {
+                line_number,
+                column_number,
+                node_index: edge.node_index,
+                nodes_len,
+            });
+        }
+    }
+
+    Ok((nodes, edge_positions))
+}
+
+/// Write all nodes and their edges in the graph file format, one line per node:
+///
+/// `{"label":"Earth"} | -> 0(5), -> 2(8),`
+///
+/// The node data is the compact JSON of `T` (without any line break), the edges are in the
+/// `neighbors` order and the weights use `Display`. The line index is the new `NodeId`: the
+/// nodes are written in the `NodeId` order without the removed nodes, so the `NodeId` after a
+/// removed node goes down. A node without data can't be written, but `with_all_nodes` already
+/// turns it into a removed node, so `nodes()` skips it.
+pub(crate) fn write_graph_file<T, G, Wr>(graph: &G, mut writer: Wr) -> io::Result<()>
+where
+    T: Debug + DeserializeOwned + Serialize,
+    G: Graph<T>,
+    Wr: Write,
+{
+    // `NodeId -> line index`
+    let line_indexes = graph
+        .nodes()
+        .enumerate()
+        .map(|(line_index, (node_index, _))| (node_index, line_index))
+        .collect::<BTreeMap<NodeId, usize>>();
+
+    for (node_index, data) in graph.nodes() {
+        write!(writer, "{} |", to_string(data)?)?;
+        for (neighbor_index, weight, _) in graph.neighbors(node_index) {
+            write!(writer, " -> {}({}),", line_indexes[&neighbor_index], weight)?;
+        }
+        writeln!(writer)?;
+    }
+
+    Ok(())
+}
+
+/// Parse `-> Connected_node_index(edge_weight)` into `(node_index, weight)`, `NaN` isn't a
+/// valid weight.
+fn parse_edge<W: Weight>(edge_str: &str) -> Option<(usize, W)> {
+    let edge_str = edge_str.trim().strip_prefix("->")?.strip_suffix(')')?;
+    let (node_index_str, weight_str) = edge_str.split_once('(')?;
+    let node_index = node_index_str.trim().parse::<usize>().ok()?;
+    let weight = weight_str.trim().parse::<W>().ok()?;
+    weight.partial_cmp(&weight).map(|_| (node_index, weight))
+}
+
+/// The 1-based `char` column of `byte_index` in `line`
+fn column_number_of(line: &str, byte_index: usize) -> usize {
+    line.char_indices()
+        .take_while(|(index, _)| *index < byte_index)
+        .count()
+        + 1
+}
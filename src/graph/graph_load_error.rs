@@ -0,0 +1,165 @@
+use std::fmt::{Debug, Display, Formatter, Result as FmtResult};
+use std::io;
+
+///
+/// # `GraphLoadError`
+///
+/// Why a graph file can't be loaded. Both `line_number` and `column_number` are start from
+/// `1`, `column_number` is counted in `char`. `W` is the edge weight of the graph.
+///
+#[derive(Debug)]
+pub enum GraphLoadError<W = usize> {
+    // The file can't be opened (`line_number` is `None`) or a line can't be read
+    Io {
+        line_number: Option<usize>,
+        error: io::Error,
+    },
+
+    // The node data (before `|`) isn't a valid JSON of `T`
+    InvalidJson {
+        line_number: usize,
+        column_number: usize,
+        message: String,
+    },
+
+    // The edge isn't in the `-> Connected_node_index(edge_weight)` format
+    InvalidEdge {
+        line_number: usize,
+        column_number: usize,
+        edge: String,
+    },
+
+    // The edge connects to a node which doesn't exist
+    DanglingEdge {
+        line_number: usize,
+        column_number: usize,
+        node_index: usize,
+        nodes_len: usize,
+    },
+
+    // `A -> B` doesn't have the edge `B -> A` back
+    AsymmetricEdge {
+        line_number: usize,
+        column_number: usize,
+        from_node: usize,
+        to_node: usize,
+    },
+
+    // `A -> B` and `B -> A` have different weights, reported at the edge in the later line
+    MismatchedWeight {
+        line_number: usize,
+        column_number: usize,
+        from_node: usize,
+        to_node: usize,
+        weight: W,
+        reverse_line_number: usize,
+        reverse_weight: W,
+    },
+
+    // No `|` between the node data and the edges, `column_number` is the end of the line
+    MissingSeparator {
+        line_number: usize,
+        column_number: usize,
+    },
+}
+
+///
+impl<W> GraphLoadError<W> {
+    /// Where the error is, `None` if it's not about a particular line.
+    pub fn line_number(&self) -> Option<usize> {
+        match self {
+            GraphLoadError::Io { line_number, .. } => *line_number,
+            GraphLoadError::InvalidJson { line_number, .. }
+            | GraphLoadError::InvalidEdge { line_number, .. }
+            | GraphLoadError::DanglingEdge { line_number, .. }
+            | GraphLoadError::AsymmetricEdge { line_number, .. }
+            | GraphLoadError::MismatchedWeight { line_number, .. }
+            | GraphLoadError::MissingSeparator { line_number, .. } => Some(*line_number),
+        }
+    }
+}
+
+///
+impl<W: Display> Display for GraphLoadError<W> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            GraphLoadError::Io {
+                line_number: None,
+                error,
+            } => write!(f, "Failed to open the graph file: {}", error),
+            GraphLoadError::Io {
+                line_number: Some(line_number),
+                error,
+            } => write!(f, "Failed to read line {}: {}", line_number, error),
+            GraphLoadError::InvalidJson {
+                line_number,
+                column_number,
+                message,
+            } => write!(
+                f,
+                "Invalid node JSON at {}:{}: {}",
+                line_number, column_number, message
+            ),
+            GraphLoadError::InvalidEdge {
+                line_number,
+                column_number,
+                edge,
+            } => write!(
+                f,
+                "Edge string '{}' at {}:{} should have the following format: '-> Connected_node_index(edge_weight)'",
+                edge, line_number, column_number
+            ),
+            GraphLoadError::DanglingEdge {
+                line_number,
+                column_number,
+                node_index,
+                nodes_len,
+            } => write!(
+                f,
+                "Edge at {}:{} connects to node {}, but the graph only has {} nodes",
+                line_number, column_number, node_index, nodes_len
+            ),
+            GraphLoadError::AsymmetricEdge {
+                line_number,
+                column_number,
+                from_node,
+                to_node,
+            } => write!(
+                f,
+                "Edge at {}:{} connects node {} to node {}, but node {} doesn't connect back",
+                line_number, column_number, from_node, to_node, to_node
+            ),
+            GraphLoadError::MismatchedWeight {
+                line_number,
+                column_number,
+                from_node,
+                to_node,
+                weight,
+                reverse_line_number,
+                reverse_weight,
+            } => write!(
+                f,
+                "Edge at {}:{} from node {} to node {} has weight {}, but the edge back at line {} has weight {}",
+                line_number, column_number, from_node, to_node, weight, reverse_line_number, reverse_weight
+            ),
+            GraphLoadError::MissingSeparator {
+                line_number,
+                column_number,
+            } => write!(
+                f,
+                "Missing '|' at {}:{}, each line should have the following format: '{{...JSON data here}} | edges_separated_by_comma'",
+                line_number, column_number
+            ),
+        }
+    }
+}
+
+///
+impl<W: Debug + Display> std::error::Error for GraphLoadError<W> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GraphLoadError::Io { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,233 @@
+use super::undirected_graph::{Graph, NodeId};
+use super::weight::Weight;
+use crate::queue::Queue;
+use serde::de::DeserializeOwned;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Debug;
+use std::ops::Sub;
+
+///
+/// # `MaxFlow`
+///
+/// The maximum flow from `source` to `sink`, each edge weight is its capacity. The edges with
+/// a weight `<= zero()` and the self-loops can't carry any flow.
+///
+/// The minimum cut is `source_side` (all nodes still reachable from `source` by the edges which
+/// aren't full) and `sink_side` (all other nodes). The capacities of `cut_edges` add up to
+/// `value`, so there isn't any bigger flow.
+///
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct MaxFlow<W = usize> {
+    pub source: NodeId,
+    pub sink: NodeId,
+
+    // Saturated, see `Weight::saturating_add`
+    pub value: W,
+
+    // `(from, to) -> flow` of each edge with a positive flow, `from -> to` follows the edge.
+    // The flows in the opposite directions cancel out, so only one of `a -> b` and `b -> a`
+    // has a flow.
+    pub edge_flows: BTreeMap<(NodeId, NodeId), W>,
+
+    // Both sides are in the `NodeId` order
+    pub source_side: Vec<NodeId>,
+    pub sink_side: Vec<NodeId>,
+
+    // `(from, to)` of the edges from `source_side` to `sink_side`, in the `NodeId` order. All of
+    // them are full.
+    pub cut_edges: Vec<(NodeId, NodeId)>,
+}
+
+///
+impl<W: Weight> MaxFlow<W> {
+    /// The flow on the edge `from -> to`, `zero()` if there isn't any.
+    pub fn flow_on(&self, from: NodeId, to: NodeId) -> W {
+        self.edge_flows
+            .get(&(from, to))
+            .copied()
+            .unwrap_or_else(W::zero)
+    }
+}
+
+/// One edge in the residual graph, each graph edge `a -> b` has the arc `a -> b` (with its
+/// capacity) and the reverse arc `b -> a` (without any capacity). They are next to each other,
+/// so the pair of the arc `i` is `i ^ 1`.
+struct ResidualArc<W> {
+    from: NodeId,
+    to: NodeId,
+    capacity: W,
+
+    // How much more can go through it, the reverse arc gets back what goes through the arc
+    residual: W,
+}
+
+/// Edmonds-Karp algorithm: find the augmenting path with the fewest arcs by breadth-first
+/// search (on `Queue`), push as much as possible through it, and repeat until `sink` isn't
+/// reachable any more. `None` if any of them doesn't exist or `source == sink`.
+///
+/// The arcs are searched in the `NodeId` order of their node, then in the `neighbors` order.
+/// So the same graph always gives back the same flows.
+pub(crate) fn edmonds_karp<T, G>(
+    graph: &G,
+    source: NodeId,
+    sink: NodeId,
+) -> Option<MaxFlow<G::Weight>>
+where
+    T: Debug + DeserializeOwned,
+    G: Graph<T>,
+    G::Weight: Sub<Output = G::Weight>,
+{
+    if graph.node(source).is_none() || graph.node(sink).is_none() || source == sink {
+        return None;
+    }
+
+    let zero = G::Weight::zero();
+    let mut arcs = Vec::<ResidualArc<G::Weight>>::new();
+    // `node_index -> the positions in `arcs` of all arcs from it`
+    let mut arcs_from = BTreeMap::<NodeId, Vec<usize>>::new();
+    for (node_index, _) in graph.nodes() {
+        arcs_from.entry(node_index).or_default();
+        for (neighbor_index, capacity, _) in graph.neighbors(node_index) {
+            if neighbor_index == node_index || capacity <= zero {
+                continue;
+            }
+
+            arcs_from.get_mut(&node_index).unwrap().push(arcs.len());
+            arcs.push(ResidualArc {
+                from: node_index,
+                to: neighbor_index,
+                capacity,
+                residual: capacity,
+            });
+            arcs_from
+                .entry(neighbor_index)
+                .or_default()
+                .push(arcs.len());
+            arcs.push(ResidualArc {
+                from: neighbor_index,
+                to: node_index,
+                capacity: zero,
+                residual: zero,
+            });
+        }
+    }
+
+    let mut value = zero;
+    loop {
+        // `node_index -> the arc to it` on the augmenting path
+        let parent_arcs = search_residual(&arcs, &arcs_from, source);
+        if !parent_arcs.contains_key(&sink) {
+            break;
+        }
+
+        let mut path_arcs = vec![];
+        let mut current_node = sink;
+        while let Some(Some(arc_index)) = parent_arcs.get(&current_node) {
+            path_arcs.push(*arc_index);
+            current_node = arcs[*arc_index].from;
+        }
+
+        let mut bottleneck = arcs[path_arcs[0]].residual;
+        for arc_index in path_arcs.iter() {
+            if arcs[*arc_index].residual < bottleneck {
+                bottleneck = arcs[*arc_index].residual;
+            }
+        }
+        for arc_index in path_arcs {
+            arcs[arc_index].residual = arcs[arc_index].residual - bottleneck;
+            arcs[arc_index ^ 1].residual = arcs[arc_index ^ 1].residual.saturating_add(bottleneck);
+        }
+        value = value.saturating_add(bottleneck);
+    }
+
+    // The flow on each graph edge is how much of its capacity is used
+    let mut edge_flows = BTreeMap::<(NodeId, NodeId), G::Weight>::new();
+    for arc in arcs.iter().step_by(2) {
+        let flow = arc.capacity - arc.residual;
+        if flow > zero {
+            let edge_flow = edge_flows.entry((arc.from, arc.to)).or_insert(zero);
+            *edge_flow = edge_flow.saturating_add(flow);
+        }
+    }
+    cancel_opposite_flows(&mut edge_flows);
+
+    let source_side = search_residual(&arcs, &arcs_from, source)
+        .into_keys()
+        .collect::<BTreeSet<NodeId>>();
+    let sink_side = arcs_from
+        .keys()
+        .filter(|node_index| !source_side.contains(node_index))
+        .copied()
+        .collect();
+    let mut cut_edges = arcs
+        .iter()
+        .step_by(2)
+        .filter(|arc| source_side.contains(&arc.from) && !source_side.contains(&arc.to))
+        .map(|arc| (arc.from, arc.to))
+        .collect::<Vec<(NodeId, NodeId)>>();
+    cut_edges.sort_unstable();
+    cut_edges.dedup();
+
+    Some(MaxFlow {
+        source,
+        sink,
+        value,
+        edge_flows,
+        source_side: source_side.into_iter().collect(),
+        sink_side,
+        cut_edges,
+    })
+}
+
+/// Breadth-first search from `source` by the arcs which aren't full, get back
+/// `node_index -> the arc to it` of all reachable nodes (`None` for `source`).
+fn search_residual<W: Weight>(
+    arcs: &[ResidualArc<W>],
+    arcs_from: &BTreeMap<NodeId, Vec<usize>>,
+    source: NodeId,
+) -> BTreeMap<NodeId, Option<usize>> {
+    let mut parent_arcs = BTreeMap::<NodeId, Option<usize>>::new();
+    let mut queue = Queue::new();
+    parent_arcs.insert(source, None);
+    queue.enqueue(source);
+
+    while let Some(node_index) = queue.dequeue() {
+        for arc_index in arcs_from[&node_index].iter() {
+            let arc = &arcs[*arc_index];
+            if arc.residual > W::zero() && !parent_arcs.contains_key(&arc.to) {
+                parent_arcs.insert(arc.to, Some(*arc_index));
+                queue.enqueue(arc.to);
+            }
+        }
+    }
+
+    parent_arcs
+}
+
+/// `a -> b` and `b -> a` can both have a flow, e.g. both directions of an `UndirectedGraph`
+/// edge. Only keep the difference in the bigger direction, it's still a valid flow with the
+/// same value.
+fn cancel_opposite_flows<W>(edge_flows: &mut BTreeMap<(NodeId, NodeId), W>)
+where
+    W: Weight + Sub<Output = W>,
+{
+    let opposite_pairs = edge_flows
+        .keys()
+        .filter(|(from, to)| from < to && edge_flows.contains_key(&(*to, *from)))
+        .copied()
+        .collect::<Vec<(NodeId, NodeId)>>();
+
+    for (a, b) in opposite_pairs {
+        let (forward_flow, backward_flow) = (edge_flows[&(a, b)], edge_flows[&(b, a)]);
+        if backward_flow < forward_flow {
+            edge_flows.insert((a, b), forward_flow - backward_flow);
+            edge_flows.remove(&(b, a));
+        } else if forward_flow < backward_flow {
+            edge_flows.insert((b, a), backward_flow - forward_flow);
+            edge_flows.remove(&(a, b));
+        } else {
+            edge_flows.remove(&(a, b));
+            edge_flows.remove(&(b, a));
+        }
+    }
+}
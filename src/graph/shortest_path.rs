@@ -0,0 +1,420 @@
+use super::filtered_search::SearchFilter;
+use super::undirected_graph::{Graph, NodeId};
+use super::weight::{OrderedWeight, Weight};
+use serde::de::DeserializeOwned;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap};
+use std::fmt::Debug;
+
+/// A path and its total weight, `nodes` starts with the `from` node and ends with the `to` node.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ShortestPath<W = usize> {
+    pub cost: W,
+    pub nodes: Vec<NodeId>,
+}
+
+///
+/// # `ShortestPathTree`
+///
+/// The shortest distance from the `source` node to all reachable nodes, and the parent of each
+/// node on its shortest path. The unreachable nodes aren't in the tree.
+///
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ShortestPathTree<W = usize> {
+    source: NodeId,
+
+    // `node_index -> (distance, parent)`
+    entries: BTreeMap<NodeId, (W, Option<NodeId>)>,
+}
+
+///
+impl<W: Weight> ShortestPathTree<W> {
+    ///
+    pub fn source(&self) -> NodeId {
+        self.source
+    }
+
+    /// `None` if the node isn't reachable
+    pub fn distance_to(&self, node_index: NodeId) -> Option<W> {
+        self.entries.get(&node_index).map(|(distance, _)| *distance)
+    }
+
+    /// The previous node on the shortest path to `node_index`, `None` for the source node or
+    /// the unreachable nodes.
+    pub fn parent_of(&self, node_index: NodeId) -> Option<NodeId> {
+        self.entries
+            .get(&node_index)
+            .and_then(|(_, parent)| *parent)
+    }
+
+    /// All `(node_index, distance)` of the reachable nodes in the `NodeId` order
+    pub fn distances(&self) -> impl Iterator<Item = (NodeId, W)> + '_ {
+        self.entries
+            .iter()
+            .map(|(node_index, (distance, _))| (*node_index, *distance))
+    }
+
+    /// Walk back from `to` by the parents, `None` if `to` isn't reachable.
+    pub fn path_to(&self, to: NodeId) -> Option<ShortestPath<W>> {
+        let cost = self.distance_to(to)?;
+        let mut nodes = vec![to];
+        let mut current_node = to;
+        while let Some(parent) = self.parent_of(current_node) {
+            nodes.push(parent);
+            current_node = parent;
+        }
+        nodes.reverse();
+
+        Some(ShortestPath { cost, nodes })
+    }
+}
+
+/// Dijkstra's algorithm with `BinaryHeap` as the priority queue, stop as soon as `target` is
+/// settled (if any).
+///
+/// Ties are broken in a deterministic way: among the nodes with the same distance, the smaller
+/// `NodeId` is settled first, and a node only changes its parent when a strictly shorter path
+/// is found. So the same graph always gives back the same paths.
+///
+/// Only the neighbors allowed by `filter` are searched, the `from` node isn't checked.
+///
+/// All weights should be `>= zero()`, use `bellman_ford` for the negative weights.
+pub(crate) fn dijkstra<T, G, F>(
+    graph: &G,
+    from: NodeId,
+    target: Option<NodeId>,
+    filter: &mut F,
+) -> ShortestPathTree<G::Weight>
+where
+    T: Debug + DeserializeOwned,
+    G: Graph<T>,
+    F: SearchFilter<T, G::Weight>,
+{
+    let mut tree = ShortestPathTree {
+        source: from,
+        entries: BTreeMap::new(),
+    };
+    if graph.node(from).is_none() {
+        return tree;
+    }
+
+    // `(distance, parent)` of the nodes which are found but not settled yet
+    let mut tentative = BTreeMap::<NodeId, (G::Weight, Option<NodeId>)>::new();
+    let mut heap = BinaryHeap::<Reverse<(OrderedWeight<G::Weight>, NodeId)>>::new();
+    tentative.insert(from, (G::Weight::zero(), None));
+    heap.push(Reverse((OrderedWeight(G::Weight::zero()), from)));
+
+    while let Some(Reverse((OrderedWeight(distance), node_index))) = heap.pop() {
+        // Outdated heap entry, the node is settled or has a shorter distance now
+        match tentative.get(&node_index) {
+            Some((tentative_distance, _)) if *tentative_distance == distance => {}
+            _ => continue,
+        }
+
+        let settled_entry = tentative.remove(&node_index).unwrap();
+        tree.entries.insert(node_index, settled_entry);
+        if target == Some(node_index) {
+            break;
+        }
+
+        for (neighbor_index, weight, neighbor_data) in graph.neighbors(node_index) {
+            if tree.entries.contains_key(&neighbor_index)
+                || !filter.allows_edge(node_index, neighbor_index, weight)
+                || !filter.allows_node(neighbor_index, neighbor_data)
+            {
+                continue;
+            }
+
+            // Too far to be counted, treat it as unreachable by this edge
+            let new_distance = match distance.checked_add(weight) {
+                Some(new_distance) => new_distance,
+                None => continue,
+            };
+
+            let is_shorter = match tentative.get(&neighbor_index) {
+                Some((old_distance, _)) => new_distance < *old_distance,
+                None => true,
+            };
+            if is_shorter {
+                tentative.insert(neighbor_index, (new_distance, Some(node_index)));
+                heap.push(Reverse((OrderedWeight(new_distance), neighbor_index)));
+            }
+        }
+    }
+
+    tree
+}
+
+/// Why there isn't any shortest path: the `nodes` make a cycle with a negative total weight,
+/// so going around it once more is always shorter. Each node has an edge to the next one and
+/// the last node has an edge back to the first one.
+///
+/// In an `UndirectedGraph`, an edge with a negative weight is already a cycle of two nodes
+/// (there and back).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct NegativeCycle {
+    pub nodes: Vec<NodeId>,
+}
+
+/// Bellman-Ford algorithm, it works with the negative weights. Fails if a negative cycle is
+/// reachable from `from`, the unreachable ones don't matter.
+///
+/// The same ties as `dijkstra`: a node only changes its parent when a strictly shorter path is
+/// found, and the nodes are relaxed in the `NodeId` order.
+pub(crate) fn bellman_ford<T, G>(
+    graph: &G,
+    from: NodeId,
+) -> Result<ShortestPathTree<G::Weight>, NegativeCycle>
+where
+    T: Debug + DeserializeOwned,
+    G: Graph<T>,
+{
+    let mut entries = BTreeMap::new();
+    if graph.node(from).is_some() {
+        entries.insert(from, (G::Weight::zero(), None));
+    }
+
+    Ok(ShortestPathTree {
+        source: from,
+        entries: relax_all_edges(graph, entries)?,
+    })
+}
+
+/// Any negative cycle in the graph, no matter where it is. Same as `bellman_ford` from a virtual
+/// node which has an edge with `zero()` weight to every node.
+pub(crate) fn find_negative_cycle<T, G>(graph: &G) -> Option<NegativeCycle>
+where
+    T: Debug + DeserializeOwned,
+    G: Graph<T>,
+{
+    let entries = graph
+        .nodes()
+        .map(|(node_index, _)| (node_index, (G::Weight::zero(), None)))
+        .collect();
+
+    relax_all_edges(graph, entries).err()
+}
+
+/// `node_index -> (distance, parent)` of the nodes found by `bellman_ford` so far
+type DistanceEntries<W> = BTreeMap<NodeId, (W, Option<NodeId>)>;
+
+/// Relax all edges from the nodes in `entries` round by round, until nothing changes.
+///
+/// Each round finds the shortest paths with one more edge, so all shortest paths are found
+/// after `nodes_len - 1` rounds. A change in the next round means a negative cycle, then the
+/// parents lead back to it.
+fn relax_all_edges<T, G>(
+    graph: &G,
+    mut entries: DistanceEntries<G::Weight>,
+) -> Result<DistanceEntries<G::Weight>, NegativeCycle>
+where
+    T: Debug + DeserializeOwned,
+    G: Graph<T>,
+{
+    let nodes_len = graph.nodes().count();
+    for round in 1..=nodes_len {
+        let mut last_changed_node = None;
+        for (node_index, _) in graph.nodes() {
+            let distance = match entries.get(&node_index) {
+                Some((distance, _)) => *distance,
+                None => continue,
+            };
+
+            for (neighbor_index, weight, _) in graph.neighbors(node_index) {
+                // Too far to be counted, treat it as unreachable by this edge
+                let new_distance = match distance.checked_add(weight) {
+                    Some(new_distance) => new_distance,
+                    None => continue,
+                };
+
+                let is_shorter = match entries.get(&neighbor_index) {
+                    Some((old_distance, _)) => new_distance < *old_distance,
+                    None => true,
+                };
+                if is_shorter {
+                    entries.insert(neighbor_index, (new_distance, Some(node_index)));
+                    last_changed_node = Some(neighbor_index);
+                }
+            }
+        }
+
+        match last_changed_node {
+            None => break,
+            Some(changed_node) if round == nodes_len => {
+                return Err(negative_cycle_of(&entries, changed_node));
+            }
+            Some(_) => {}
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Walk back from `start` by the parents until a node is visited twice, the nodes since its
+/// first visit are the negative cycle.
+fn negative_cycle_of<W>(entries: &DistanceEntries<W>, start: NodeId) -> NegativeCycle {
+    let mut walked_nodes = vec![];
+    let mut walked_positions = BTreeMap::<NodeId, usize>::new();
+    let mut current_node = start;
+    while !walked_positions.contains_key(&current_node) {
+        walked_positions.insert(current_node, walked_nodes.len());
+        walked_nodes.push(current_node);
+        // A node changed in the last round is on or after a negative cycle of the parents, so
+        // walking back never reaches a starting node (without any parent)
+        current_node = entries[&current_node].1.unwrap();
+    }
+
+    let mut nodes = walked_nodes.split_off(walked_positions[&current_node]);
+    nodes.reverse();
+    NegativeCycle { nodes }
+}
+
+/// The A* search result. `nodes_expanded` is how many times a node is taken out of the open
+/// set to expand its neighbors (including the `to` node), the better the heuristic the fewer.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct AStarResult<W = usize> {
+    // `None` if `to` isn't reachable
+    pub path: Option<ShortestPath<W>>,
+    pub nodes_expanded: usize,
+}
+
+/// A* search, `heuristic(node_data, to_data)` estimates the cost from a node to `to`.
+///
+/// The path is the shortest one if the heuristic never overestimates (admissible). A node is
+/// expanded again if a shorter path to it is found later, so the heuristic doesn't have to be
+/// consistent. Ties of `cost + estimation` are broken by the smaller estimation, then the
+/// smaller `NodeId`.
+///
+/// Only the neighbors allowed by `filter` are searched, the `from` node isn't checked.
+pub(crate) fn a_star<T, G, H, F>(
+    graph: &G,
+    from: NodeId,
+    to: NodeId,
+    mut heuristic: H,
+    filter: &mut F,
+) -> AStarResult<G::Weight>
+where
+    T: Debug + DeserializeOwned,
+    G: Graph<T>,
+    H: FnMut(&T, &T) -> G::Weight,
+    F: SearchFilter<T, G::Weight>,
+{
+    let mut result = AStarResult {
+        path: None,
+        nodes_expanded: 0,
+    };
+    let to_data = match (graph.node(from), graph.node(to)) {
+        (Some(_), Some(to_data)) => to_data,
+        _ => return result,
+    };
+
+    // `node_index -> (cost, parent)` of the best path found so far
+    let mut best_paths = BTreeMap::<NodeId, (G::Weight, Option<NodeId>)>::new();
+    let mut open_set =
+        BinaryHeap::<Reverse<(OrderedWeight<G::Weight>, OrderedWeight<G::Weight>, NodeId)>>::new();
+    let from_estimation = heuristic(graph.node(from).unwrap(), to_data);
+    best_paths.insert(from, (G::Weight::zero(), None));
+    open_set.push(Reverse((
+        OrderedWeight(from_estimation),
+        OrderedWeight(from_estimation),
+        from,
+    )));
+
+    while let Some(Reverse((OrderedWeight(priority), OrderedWeight(estimation), node_index))) =
+        open_set.pop()
+    {
+        // Outdated entry, a shorter path to the node was found after pushing it
+        let cost = best_paths[&node_index].0;
+        if cost.saturating_add(estimation) != priority {
+            continue;
+        }
+
+        result.nodes_expanded += 1;
+        if node_index == to {
+            let mut nodes = vec![to];
+            let mut current_node = to;
+            while let Some(parent) = best_paths[&current_node].1 {
+                nodes.push(parent);
+                current_node = parent;
+            }
+            nodes.reverse();
+            result.path = Some(ShortestPath { cost, nodes });
+            break;
+        }
+
+        for (neighbor_index, weight, neighbor_data) in graph.neighbors(node_index) {
+            if !filter.allows_edge(node_index, neighbor_index, weight)
+                || !filter.allows_node(neighbor_index, neighbor_data)
+            {
+                continue;
+            }
+
+            let new_cost = match cost.checked_add(weight) {
+                Some(new_cost) => new_cost,
+                None => continue,
+            };
+
+            let is_shorter = match best_paths.get(&neighbor_index) {
+                Some((old_cost, _)) => new_cost < *old_cost,
+                None => true,
+            };
+            if is_shorter {
+                let neighbor_estimation = heuristic(neighbor_data, to_data);
+                best_paths.insert(neighbor_index, (new_cost, Some(node_index)));
+                open_set.push(Reverse((
+                    OrderedWeight(new_cost.saturating_add(neighbor_estimation)),
+                    OrderedWeight(neighbor_estimation),
+                    neighbor_index,
+                )));
+            }
+        }
+    }
+
+    result
+}
+
+/// Heuristic for `a_star`: the straight-line distance between the positions (rounded down, so
+/// it never overestimates more than the real distance). `position_of` gets back the node
+/// position, the estimation is `0` if any of the positions is unknown.
+///
+/// ```
+/// # use data_structure_implementation_by_rust::graph::shortest_path::euclidean_distance;
+/// # use data_structure_implementation_by_rust::graph::undirected_graph::*;
+/// # #[derive(Debug, serde::Deserialize)]
+/// # #[serde(rename_all = "camelCase")]
+/// # struct Planet {
+/// #     relative_position: Option<(isize, isize)>,
+/// # }
+/// # let graph = UndirectedGraph::<Planet>::load_from_file("alien_map_1.txt").unwrap();
+/// # let (home, mars) = (0, 2);
+/// let result = graph.a_star(
+///     home,
+///     mars,
+///     euclidean_distance(|planet: &Planet| planet.relative_position),
+/// );
+/// ```
+pub fn euclidean_distance<T, P>(position_of: P) -> impl Fn(&T, &T) -> usize
+where
+    P: Fn(&T) -> Option<(isize, isize)>,
+{
+    move |a, b| match (position_of(a), position_of(b)) {
+        (Some((ax, ay)), Some((bx, by))) => {
+            let dx = ax.abs_diff(bx) as f64;
+            let dy = ay.abs_diff(by) as f64;
+            (dx * dx + dy * dy).sqrt().floor() as usize
+        }
+        _ => 0,
+    }
+}
+
+/// Heuristic for `a_star`: `|dx| + |dy|` between the positions, it's only admissible when moving
+/// along the grid. `position_of` works in the same way as `euclidean_distance`.
+pub fn manhattan_distance<T, P>(position_of: P) -> impl Fn(&T, &T) -> usize
+where
+    P: Fn(&T) -> Option<(isize, isize)>,
+{
+    move |a, b| match (position_of(a), position_of(b)) {
+        (Some((ax, ay)), Some((bx, by))) => ax.abs_diff(bx).saturating_add(ay.abs_diff(by)),
+        _ => 0,
+    }
+}
@@ -0,0 +1,205 @@
+use super::undirected_graph::{Graph, NodeId};
+use super::weight::{OrderedWeight, Weight};
+use crate::union_find::UnionFind;
+use serde::de::DeserializeOwned;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap};
+use std::fmt::Debug;
+
+/// An undirected edge, `a <= b` always
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct SpanningEdge<W = usize> {
+    pub a: NodeId,
+    pub b: NodeId,
+    pub weight: W,
+}
+
+///
+impl<W: Weight> SpanningEdge<W> {
+    ///
+    fn new(a: NodeId, b: NodeId, weight: W) -> Self {
+        SpanningEdge {
+            a: a.min(b),
+            b: a.max(b),
+            weight,
+        }
+    }
+
+    /// Edges are picked by the smaller weight, then the smaller `a`, then the smaller `b`
+    fn order_key(&self) -> (OrderedWeight<W>, NodeId, NodeId) {
+        (OrderedWeight(self.weight), self.a, self.b)
+    }
+}
+
+/// The minimum spanning tree of one connected component
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SpanningTree<W = usize> {
+    // All nodes in the component, in the `NodeId` order
+    pub nodes: Vec<NodeId>,
+
+    // `nodes.len() - 1` edges, in the picking order (see `SpanningEdge::order_key`)
+    pub edges: Vec<SpanningEdge<W>>,
+
+    // Saturated, see `Weight::saturating_add`
+    pub total_weight: W,
+}
+
+///
+/// # `SpanningForest`
+///
+/// The minimum spanning forest: one `SpanningTree` per connected component, ordered by their
+/// smallest `NodeId`. A connected graph gives back only one tree, an isolated node is a tree
+/// without any edge.
+///
+/// Ties are broken by `(weight, a, b)` of the edges, which is a total order, so the minimum
+/// forest is unique and both `prim` and `kruskal` give back exactly the same result.
+///
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SpanningForest<W = usize> {
+    pub trees: Vec<SpanningTree<W>>,
+
+    // Saturated, see `Weight::saturating_add`
+    pub total_weight: W,
+}
+
+///
+impl<W: Weight> SpanningForest<W> {
+    /// All edges of all trees
+    pub fn edges(&self) -> impl Iterator<Item = &SpanningEdge<W>> {
+        self.trees.iter().flat_map(|tree| tree.edges.iter())
+    }
+
+    /// Whether it's one tree which spans the whole graph
+    pub fn is_spanning_tree(&self) -> bool {
+        self.trees.len() == 1
+    }
+
+    ///
+    fn from_trees(mut trees: Vec<SpanningTree<W>>) -> Self {
+        for tree in trees.iter_mut() {
+            tree.nodes.sort_unstable();
+            tree.edges.sort_unstable_by_key(SpanningEdge::order_key);
+            tree.total_weight = tree
+                .edges
+                .iter()
+                .fold(W::zero(), |total, edge| total.saturating_add(edge.weight));
+        }
+        trees.sort_unstable_by_key(|tree| tree.nodes[0]);
+
+        let total_weight = trees.iter().fold(W::zero(), |total, tree| {
+            total.saturating_add(tree.total_weight)
+        });
+        SpanningForest {
+            trees,
+            total_weight,
+        }
+    }
+}
+
+/// Prim's algorithm, grow a tree from the smallest `NodeId` not in any tree yet, always take
+/// the cheapest edge to a new node (`BinaryHeap` as the priority queue). Self-loops are ignored.
+pub(crate) fn prim<T, G>(graph: &G) -> SpanningForest<G::Weight>
+where
+    T: Debug + DeserializeOwned,
+    G: Graph<T>,
+{
+    let mut in_tree = BTreeSet::<NodeId>::new();
+    let mut trees = Vec::new();
+
+    for (start, _) in graph.nodes() {
+        if in_tree.contains(&start) {
+            continue;
+        }
+
+        let mut tree = SpanningTree {
+            nodes: vec![],
+            edges: vec![],
+            total_weight: G::Weight::zero(),
+        };
+        // `(edge order key, the node out of the tree)`
+        let mut heap =
+            BinaryHeap::<Reverse<((OrderedWeight<G::Weight>, NodeId, NodeId), NodeId)>>::new();
+        let mut next_node = Some((start, None));
+
+        while let Some((node_index, edge)) = next_node.take() {
+            in_tree.insert(node_index);
+            tree.nodes.push(node_index);
+            tree.edges.extend(edge);
+
+            for (neighbor_index, weight, _) in graph.neighbors(node_index) {
+                if !in_tree.contains(&neighbor_index) {
+                    let edge = SpanningEdge::new(node_index, neighbor_index, weight);
+                    heap.push(Reverse((edge.order_key(), neighbor_index)));
+                }
+            }
+
+            // Skip the outdated entries, their node has been added by a cheaper edge
+            while let Some(Reverse(((OrderedWeight(weight), a, b), node_index))) = heap.pop() {
+                if !in_tree.contains(&node_index) {
+                    next_node = Some((node_index, Some(SpanningEdge { a, b, weight })));
+                    break;
+                }
+            }
+        }
+
+        trees.push(tree);
+    }
+
+    SpanningForest::from_trees(trees)
+}
+
+/// Kruskal's algorithm, take all edges from the cheapest one, skip the edges which would make
+/// a cycle (both nodes are in the same `UnionFind` set already). Self-loops are ignored.
+pub(crate) fn kruskal<T, G>(graph: &G) -> SpanningForest<G::Weight>
+where
+    T: Debug + DeserializeOwned,
+    G: Graph<T>,
+{
+    // The removed nodes keep their `NodeId`, so the set covers up to the largest live one
+    let union_find_len = graph
+        .nodes()
+        .map(|(node_index, _)| node_index + 1)
+        .max()
+        .unwrap_or(0);
+    let mut union_find = UnionFind::new(union_find_len);
+
+    // Each edge is in both nodes' neighbors, only keep the `a < b` one
+    let mut edges = graph
+        .nodes()
+        .flat_map(|(node_index, _)| {
+            graph
+                .neighbors(node_index)
+                .filter(move |(neighbor_index, _, _)| node_index < *neighbor_index)
+                .map(move |(neighbor_index, weight, _)| {
+                    SpanningEdge::new(node_index, neighbor_index, weight)
+                })
+        })
+        .collect::<Vec<SpanningEdge<G::Weight>>>();
+    edges.sort_unstable_by_key(SpanningEdge::order_key);
+
+    let picked_edges = edges
+        .into_iter()
+        .filter(|edge| union_find.union(edge.a, edge.b))
+        .collect::<Vec<SpanningEdge<G::Weight>>>();
+
+    // `root -> tree`, so the trees are ordered by the root for now
+    let mut trees = BTreeMap::<NodeId, SpanningTree<G::Weight>>::new();
+    for (node_index, _) in graph.nodes() {
+        let root = union_find.find(node_index);
+        trees
+            .entry(root)
+            .or_insert_with(|| SpanningTree {
+                nodes: vec![],
+                edges: vec![],
+                total_weight: G::Weight::zero(),
+            })
+            .nodes
+            .push(node_index);
+    }
+    for edge in picked_edges {
+        let root = union_find.find(edge.a);
+        trees.get_mut(&root).unwrap().edges.push(edge);
+    }
+
+    SpanningForest::from_trees(trees.into_values().collect())
+}
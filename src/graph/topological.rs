@@ -0,0 +1,243 @@
+use super::directed_graph::DirectedGraph;
+use super::traversal::{DfsEvent, DfsEvents};
+use super::undirected_graph::{Graph, NodeId};
+use crate::queue::Queue;
+use serde::de::DeserializeOwned;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Debug;
+
+/// Why there isn't any topological order: the `nodes` make a cycle, each node has an edge to
+/// the next one and the last node has an edge back to the first one. A self-loop is a cycle
+/// of one node.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct DirectedCycle {
+    pub nodes: Vec<NodeId>,
+}
+
+///
+/// # `StronglyConnectedComponents`
+///
+/// Each component is a maximal set of nodes which can all reach each other. The component ids
+/// are `0..count()` in a topological order of the condensation, so an edge between two
+/// components always goes from the smaller id to the bigger one.
+///
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct StronglyConnectedComponents {
+    // `node_index -> component id`
+    component_ids: BTreeMap<NodeId, usize>,
+
+    // The nodes of each component, in the `NodeId` order
+    components: Vec<Vec<NodeId>>,
+}
+
+///
+impl StronglyConnectedComponents {
+    /// `None` if the node doesn't exist
+    pub fn component_of(&self, node_index: NodeId) -> Option<usize> {
+        self.component_ids.get(&node_index).copied()
+    }
+
+    ///
+    pub fn count(&self) -> usize {
+        self.components.len()
+    }
+
+    /// `None` if there isn't such component
+    pub fn nodes_in(&self, component_id: usize) -> Option<&[NodeId]> {
+        self.components.get(component_id).map(Vec::as_slice)
+    }
+
+    /// The nodes of all components, in the component id order
+    pub fn components(&self) -> impl Iterator<Item = &[NodeId]> {
+        self.components.iter().map(Vec::as_slice)
+    }
+}
+
+/// Kahn's algorithm on `Queue`: take the nodes without any incoming edge first, then remove
+/// their edges, and repeat. The nodes which are never taken are all on or after a cycle, then
+/// walking back by the incoming edges among them always ends up in a cycle.
+///
+/// The nodes without any incoming edge are taken in the `NodeId` order, so the same graph
+/// always gives back the same order.
+pub(crate) fn topological_sort<T, G>(graph: &G) -> Result<Vec<NodeId>, DirectedCycle>
+where
+    T: Debug + DeserializeOwned,
+    G: Graph<T>,
+{
+    let mut in_degrees = graph
+        .nodes()
+        .map(|(node_index, _)| (node_index, 0usize))
+        .collect::<BTreeMap<NodeId, usize>>();
+    for (node_index, _) in graph.nodes() {
+        for (neighbor_index, _, _) in graph.neighbors(node_index) {
+            *in_degrees.get_mut(&neighbor_index).unwrap() += 1;
+        }
+    }
+
+    let mut queue = Queue::new();
+    for (node_index, in_degree) in in_degrees.iter() {
+        if *in_degree == 0 {
+            queue.enqueue(*node_index);
+        }
+    }
+
+    let mut sorted_nodes = Vec::with_capacity(in_degrees.len());
+    while let Some(node_index) = queue.dequeue() {
+        sorted_nodes.push(node_index);
+        for (neighbor_index, _, _) in graph.neighbors(node_index) {
+            let in_degree = in_degrees.get_mut(&neighbor_index).unwrap();
+            *in_degree -= 1;
+            if *in_degree == 0 {
+                queue.enqueue(neighbor_index);
+            }
+        }
+    }
+
+    if sorted_nodes.len() == in_degrees.len() {
+        return Ok(sorted_nodes);
+    }
+
+    // Each left node has at least one incoming edge from the other left nodes
+    let left_nodes = in_degrees
+        .into_iter()
+        .filter(|(_, in_degree)| *in_degree > 0)
+        .map(|(node_index, _)| node_index)
+        .collect::<BTreeSet<NodeId>>();
+    let mut predecessors = BTreeMap::<NodeId, NodeId>::new();
+    for node_index in left_nodes.iter().copied() {
+        for (neighbor_index, _, _) in graph.neighbors(node_index) {
+            if left_nodes.contains(&neighbor_index) {
+                predecessors.entry(neighbor_index).or_insert(node_index);
+            }
+        }
+    }
+
+    // Walk back until a node is visited twice, the nodes since its first visit are the cycle
+    let mut walked_nodes = vec![];
+    let mut walked_positions = BTreeMap::<NodeId, usize>::new();
+    let mut current_node = *left_nodes.iter().next().unwrap();
+    while !walked_positions.contains_key(&current_node) {
+        walked_positions.insert(current_node, walked_nodes.len());
+        walked_nodes.push(current_node);
+        current_node = predecessors[&current_node];
+    }
+
+    let mut nodes = walked_nodes.split_off(walked_positions[&current_node]);
+    nodes.reverse();
+    Err(DirectedCycle { nodes })
+}
+
+/// Tarjan's algorithm on top of `DfsEvents`, so it's iterative rather than recursive.
+///
+/// `low[u]` is the smallest discover time reachable from the subtree of `u` by one edge to a
+/// node which isn't in any finished component yet. It's known when `u` finishes, and `u` is the
+/// first node of a component if `low[u] == discover[u]`, then that component is all nodes
+/// discovered since `u` and not in any component yet.
+pub(crate) fn strongly_connected_components<T, G>(graph: &G) -> StronglyConnectedComponents
+where
+    T: Debug + DeserializeOwned,
+    G: Graph<T>,
+{
+    let mut discover_times = BTreeMap::<NodeId, usize>::new();
+    let mut parents = BTreeMap::<NodeId, Option<NodeId>>::new();
+    let mut low_times = BTreeMap::<NodeId, usize>::new();
+    // The discovered nodes which aren't in any component yet
+    let mut pending_nodes = Vec::<NodeId>::new();
+    let mut component_ids = BTreeMap::<NodeId, usize>::new();
+    // In the reverse topological order for now
+    let mut components = Vec::<Vec<NodeId>>::new();
+
+    for event in DfsEvents::forest(graph) {
+        match event {
+            DfsEvent::Discover { visit, time } => {
+                discover_times.insert(visit.node_index, time);
+                parents.insert(visit.node_index, visit.parent);
+                pending_nodes.push(visit.node_index);
+            }
+            DfsEvent::Finish { node_index, .. } => {
+                let discover_time = discover_times[&node_index];
+                let mut low_time = discover_time;
+                for (neighbor_index, _, _) in graph.neighbors(node_index) {
+                    if component_ids.contains_key(&neighbor_index) {
+                        continue;
+                    }
+
+                    let neighbor_low_time = match parents[&neighbor_index] {
+                        Some(neighbor_parent) if neighbor_parent == node_index => {
+                            low_times[&neighbor_index]
+                        }
+                        _ => discover_times[&neighbor_index],
+                    };
+                    low_time = low_time.min(neighbor_low_time);
+                }
+                low_times.insert(node_index, low_time);
+
+                if low_time == discover_time {
+                    // All nodes above `node_index` on `pending_nodes` are in its component
+                    let mut component = vec![];
+                    while let Some(pending_node) = pending_nodes.pop() {
+                        component.push(pending_node);
+                        if pending_node == node_index {
+                            break;
+                        }
+                    }
+                    component.sort_unstable();
+                    for component_node in component.iter() {
+                        component_ids.insert(*component_node, components.len());
+                    }
+                    components.push(component);
+                }
+            }
+        }
+    }
+
+    // Tarjan's algorithm finishes a component after all components reachable from it
+    let last_id = components.len().saturating_sub(1);
+    components.reverse();
+    for component_id in component_ids.values_mut() {
+        *component_id = last_id - *component_id;
+    }
+
+    StronglyConnectedComponents {
+        component_ids,
+        components,
+    }
+}
+
+/// One node per strongly connected component (the node data is its nodes), with the same id.
+/// The edges between two components become one edge with the smallest weight, the edges in a
+/// component are dropped. So it's always acyclic, and `0..count()` is a topological order.
+pub(crate) fn condensation<T, G>(
+    graph: &G,
+    components: &StronglyConnectedComponents,
+) -> DirectedGraph<Vec<NodeId>, G::Weight>
+where
+    T: Debug + DeserializeOwned,
+    G: Graph<T>,
+{
+    // `(from_component, to_component) -> weight`
+    let mut edges = BTreeMap::<(usize, usize), G::Weight>::new();
+    for (node_index, component_id) in components.component_ids.iter() {
+        for (neighbor_index, weight, _) in graph.neighbors(*node_index) {
+            let neighbor_component_id = components.component_ids[&neighbor_index];
+            if neighbor_component_id != *component_id {
+                let edge_weight = edges
+                    .entry((*component_id, neighbor_component_id))
+                    .or_insert(weight);
+                if weight < *edge_weight {
+                    *edge_weight = weight;
+                }
+            }
+        }
+    }
+
+    let mut condensation = DirectedGraph::with_all_nodes(vec![]);
+    for component in components.components.iter() {
+        condensation.add_node(component.clone());
+    }
+    for ((from, to), weight) in edges {
+        condensation.add_edge(from, to, weight).unwrap();
+    }
+
+    condensation
+}
@@ -0,0 +1,284 @@
+use super::undirected_graph::{Graph, NodeId};
+use crate::queue::Queue;
+use crate::stack::stack::Stack;
+use serde::de::DeserializeOwned;
+use std::collections::HashSet;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+/// A node reached by the traversal
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct TraversalVisit {
+    pub node_index: NodeId,
+
+    // How many edges from the start node (on the traversal tree)
+    pub depth: usize,
+
+    // The node which discovers this node, `None` for the start node
+    pub parent: Option<NodeId>,
+
+    // `0` for the start node, `1` for the next visited node, etc.
+    pub order: usize,
+}
+
+///
+/// # `Bfs`
+///
+/// Breadth-first traversal, built on `Queue`. It's lazy, the next node is only visited when
+/// calling `next()`. Each node is visited once, the `depth` is the fewest edges from the
+/// start node.
+///
+pub struct Bfs<'a, T: Debug + DeserializeOwned, G: Graph<T>> {
+    graph: &'a G,
+
+    // The discovered but not visited nodes, `order` is set when visiting
+    queue: Queue<TraversalVisit>,
+    discovered: HashSet<NodeId>,
+    visited_count: usize,
+
+    _data: PhantomData<&'a T>,
+}
+
+///
+impl<'a, T: Debug + DeserializeOwned, G: Graph<T>> Bfs<'a, T, G> {
+    /// Nothing is visited if the `start` node doesn't exist
+    pub fn new(graph: &'a G, start: NodeId) -> Self {
+        let mut bfs = Bfs {
+            graph,
+            queue: Queue::new(),
+            discovered: HashSet::new(),
+            visited_count: 0,
+            _data: PhantomData,
+        };
+
+        if graph.node(start).is_some() {
+            bfs.discovered.insert(start);
+            bfs.queue.enqueue(TraversalVisit {
+                node_index: start,
+                depth: 0,
+                parent: None,
+                order: 0,
+            });
+        }
+
+        bfs
+    }
+}
+
+///
+impl<'a, T: Debug + DeserializeOwned, G: Graph<T>> Iterator for Bfs<'a, T, G> {
+    type Item = TraversalVisit;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut visit = self.queue.dequeue()?;
+        visit.order = self.visited_count;
+        self.visited_count += 1;
+
+        for (neighbor_index, _, _) in self.graph.neighbors(visit.node_index) {
+            if self.discovered.insert(neighbor_index) {
+                self.queue.enqueue(TraversalVisit {
+                    node_index: neighbor_index,
+                    depth: visit.depth + 1,
+                    parent: Some(visit.node_index),
+                    order: 0,
+                });
+            }
+        }
+
+        Some(visit)
+    }
+}
+
+/// `Discover` happens before visiting any neighbor (pre-order), `Finish` happens after all
+/// neighbors are finished (post-order). `time` is shared by both events, it goes up by `1`
+/// for each event, so `discover_time < descendant times < finish_time`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DfsEvent {
+    Discover { visit: TraversalVisit, time: usize },
+    Finish { node_index: NodeId, time: usize },
+}
+
+/// A node on the DFS path, with its neighbors which are not tried yet.
+#[derive(Debug, PartialEq, Clone)]
+struct DfsFrame {
+    node_index: NodeId,
+    depth: usize,
+
+    // In reversed order, so the first neighbor is popped first
+    pending_neighbors: Vec<NodeId>,
+}
+
+///
+/// # `DfsEvents`
+///
+/// Depth-first traversal, built on `Stack` rather than recursion, so it never overflows the
+/// call stack on a deep graph. It's lazy and gives back both `Discover` and `Finish` events,
+/// for the algorithms which need the finish times.
+///
+pub struct DfsEvents<'a, T: Debug + DeserializeOwned, G: Graph<T>> {
+    graph: &'a G,
+
+    // The current DFS path, the top is the node being visited
+    stack: Stack<DfsFrame>,
+    discovered: HashSet<NodeId>,
+    discovered_count: usize,
+    time: usize,
+
+    // The start node is discovered when creating
+    start_event: Option<DfsEvent>,
+
+    // The next start nodes after the current DFS tree is finished (for `forest`), in reversed
+    // order so the smallest `NodeId` is popped first
+    pending_roots: Vec<NodeId>,
+
+    _data: PhantomData<&'a T>,
+}
+
+///
+impl<'a, T: Debug + DeserializeOwned, G: Graph<T>> DfsEvents<'a, T, G> {
+    /// Nothing is visited if the `start` node doesn't exist
+    pub fn new(graph: &'a G, start: NodeId) -> Self {
+        let mut dfs_events = DfsEvents {
+            graph,
+            stack: Stack::new(),
+            discovered: HashSet::new(),
+            discovered_count: 0,
+            time: 0,
+            start_event: None,
+            pending_roots: vec![],
+            _data: PhantomData,
+        };
+
+        if graph.node(start).is_some() {
+            dfs_events.start_event = Some(dfs_events.discover(start, 0, None));
+        }
+
+        dfs_events
+    }
+
+    /// Visit all nodes: start from the smallest `NodeId`, then start again from the smallest
+    /// one which isn't discovered yet, and so on. A node is never discovered twice even if a
+    /// later start node can reach it (in a `DirectedGraph`). `time` keeps going up across the
+    /// DFS trees, each start node has `parent: None` and `depth: 0`.
+    pub fn forest(graph: &'a G) -> Self {
+        let mut pending_roots = graph
+            .nodes()
+            .map(|(node_index, _)| node_index)
+            .collect::<Vec<NodeId>>();
+        pending_roots.reverse();
+
+        DfsEvents {
+            graph,
+            stack: Stack::new(),
+            discovered: HashSet::new(),
+            discovered_count: 0,
+            time: 0,
+            start_event: None,
+            pending_roots,
+            _data: PhantomData,
+        }
+    }
+
+    ///
+    fn discover(&mut self, node_index: NodeId, depth: usize, parent: Option<NodeId>) -> DfsEvent {
+        let mut pending_neighbors = self
+            .graph
+            .neighbors(node_index)
+            .map(|(neighbor_index, _, _)| neighbor_index)
+            .collect::<Vec<NodeId>>();
+        pending_neighbors.reverse();
+
+        self.discovered.insert(node_index);
+        self.stack.push(DfsFrame {
+            node_index,
+            depth,
+            pending_neighbors,
+        });
+
+        let event = DfsEvent::Discover {
+            visit: TraversalVisit {
+                node_index,
+                depth,
+                parent,
+                order: self.discovered_count,
+            },
+            time: self.time,
+        };
+        self.discovered_count += 1;
+        self.time += 1;
+        event
+    }
+}
+
+///
+impl<'a, T: Debug + DeserializeOwned, G: Graph<T>> Iterator for DfsEvents<'a, T, G> {
+    type Item = DfsEvent;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(start_event) = self.start_event.take() {
+            return Some(start_event);
+        }
+
+        // `Stack` only gives back the immutable top, so the top frame is popped, updated, and
+        // pushed back if it still has pending neighbors. Both are `O(1)`, the top is the head
+        // of the inner list.
+        while let Some(mut frame) = self.stack.pop() {
+            match frame.pending_neighbors.pop() {
+                Some(neighbor_index) => {
+                    let (node_index, depth) = (frame.node_index, frame.depth);
+                    self.stack.push(frame);
+                    if !self.discovered.contains(&neighbor_index) {
+                        return Some(self.discover(neighbor_index, depth + 1, Some(node_index)));
+                    }
+                }
+                None => {
+                    let event = DfsEvent::Finish {
+                        node_index: frame.node_index,
+                        time: self.time,
+                    };
+                    self.time += 1;
+                    return Some(event);
+                }
+            }
+        }
+
+        while let Some(root) = self.pending_roots.pop() {
+            if !self.discovered.contains(&root) {
+                return Some(self.discover(root, 0, None));
+            }
+        }
+
+        None
+    }
+}
+
+///
+/// # `Dfs`
+///
+/// Same as `DfsEvents`, but only gives back the visited nodes in pre-order.
+///
+pub struct Dfs<'a, T: Debug + DeserializeOwned, G: Graph<T>> {
+    events: DfsEvents<'a, T, G>,
+}
+
+///
+impl<'a, T: Debug + DeserializeOwned, G: Graph<T>> Dfs<'a, T, G> {
+    /// Nothing is visited if the `start` node doesn't exist
+    pub fn new(graph: &'a G, start: NodeId) -> Self {
+        Dfs {
+            events: DfsEvents::new(graph, start),
+        }
+    }
+}
+
+///
+impl<'a, T: Debug + DeserializeOwned, G: Graph<T>> Iterator for Dfs<'a, T, G> {
+    type Item = TraversalVisit;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.events.find_map(|event| match event {
+            DfsEvent::Discover { visit, .. } => Some(visit),
+            DfsEvent::Finish { .. } => None,
+        })
+    }
+}
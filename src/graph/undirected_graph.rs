@@ -1,41 +1,103 @@
+use super::all_pairs_shortest_path::{floyd_warshall, AllPairsShortestPaths};
+use super::biconnectivity::{biconnectivity, BiconnectedComponent, Biconnectivity};
+use super::connectivity::{are_connected, connected_components, ConnectedComponents};
+use super::cycle::{bipartition, find_cycle, Bipartition};
+use super::filtered_search::{
+    filtered_a_star, filtered_bfs_path, filtered_dijkstra, FilteredAStarResult, NoFilter,
+    PathSearchOutcome,
+};
+use super::graph_file::{read_graph_file, write_graph_file};
+use super::graph_load_error::GraphLoadError;
+use super::max_flow::{edmonds_karp, MaxFlow};
+use super::shortest_path::{
+    a_star, bellman_ford, dijkstra, find_negative_cycle, AStarResult, NegativeCycle, ShortestPath,
+    ShortestPathTree,
+};
+use super::spanning_tree::{kruskal, prim, SpanningForest};
+use super::traversal::{Bfs, Dfs, DfsEvents};
+use super::weight::Weight;
 use serde::de::DeserializeOwned;
-use serde::{Deserialize, Serialize};
-use serde_json::from_str;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt::Debug;
 use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
+use std::io::{self, BufWriter, Write};
+use std::ops::Sub;
+
+/// The node index in the graph node array, it never changes even after other nodes are removed.
+pub type NodeId = usize;
 
 ///
 ///
 ///
 #[derive(Debug)]
-pub struct GraphNodeEdge {
+pub struct GraphNodeEdge<W = usize> {
     // The node index in the graph node array
     pub node_index: usize,
 
     // The weight of the edge
-    pub weight: usize,
+    pub weight: W,
 }
 
 ///
 ///
 ///
 #[derive(Debug)]
-pub struct GraphNode<T: Debug + DeserializeOwned> {
-    // Generic data that binds with the current node/vertex
+pub struct GraphNode<T: Debug + DeserializeOwned, W = usize> {
+    // Generic data that binds with the current node/vertex, `None` means a removed node and
+    // `Graph::with_all_nodes` drops all its edges
     pub data: Option<T>,
 
     // All nodes that connects with `edges`
-    pub neighbors: Vec<GraphNodeEdge>,
+    pub neighbors: Vec<GraphNodeEdge<W>>,
+}
+
+/// The nodes without data are the same as the removed nodes: drop their edges (and all edges
+/// to them), and give back their `NodeId` as the tombstones. The edges to a `NodeId` out of
+/// `nodes` are dropped too, so the graph never has to check an edge before indexing with it.
+pub(crate) fn remove_nodes_without_data<T: Debug + DeserializeOwned, W>(
+    nodes: &mut [GraphNode<T, W>],
+) -> BTreeSet<NodeId> {
+    let nodes_len = nodes.len();
+    let removed_nodes = nodes
+        .iter()
+        .enumerate()
+        .filter(|(_, node)| node.data.is_none())
+        .map(|(node_index, _)| node_index)
+        .collect::<BTreeSet<NodeId>>();
+
+    for (node_index, node) in nodes.iter_mut().enumerate() {
+        if removed_nodes.contains(&node_index) {
+            node.neighbors.clear();
+        } else {
+            node.neighbors.retain(|edge| {
+                edge.node_index < nodes_len && !removed_nodes.contains(&edge.node_index)
+            });
+        }
+    }
+
+    removed_nodes
 }
 
 ///
 ///
 ///
 pub trait Graph<T: Debug + DeserializeOwned> {
-    fn with_first_node(first_node: GraphNode<T>) -> Self;
-    fn with_all_nodes(nodes: Vec<GraphNode<T>>) -> Self;
+    /// The edge weight, `usize` by default (see `Weight`)
+    type Weight: Weight;
+
+    fn with_first_node(first_node: GraphNode<T, Self::Weight>) -> Self;
+
+    /// The node without data is treated as a removed node: it keeps its `NodeId`, but it isn't
+    /// counted by `nodes_len` and it isn't in `nodes`.
+    ///
+    /// # Dropped edges
+    ///
+    /// All edges from or to a node without data, and all edges to a `NodeId` that isn't in
+    /// `nodes`, are dropped and can't be got back from the graph. Give the node its data before
+    /// building the graph to keep its edges.
+    ///
+    fn with_all_nodes(nodes: Vec<GraphNode<T, Self::Weight>>) -> Self;
 
     /// Load an entire graph instance from file
     ///
@@ -56,7 +118,8 @@ pub trait Graph<T: Debug + DeserializeOwned> {
     ///
     ///     To be able to load any data struct from a file, that node data part has to be a JSON format, that's why `<T>` has to implement the `Deserialize` trait.
     ///
-    /// ```no_run
+    /// ```
+    /// # use serde::Deserialize;
     /// #[derive(Deserialize)]
     /// #[serde(rename_all = "camelCase")]
     /// struct Planet {
@@ -94,15 +157,27 @@ pub trait Graph<T: Debug + DeserializeOwned> {
     ///
     ///     Here is the example:
     ///
-    ///     ```no_run
+    ///     ```text
     ///     {"name": "Alien Home"} |  -> 1(5),
     ///     {"name": "Earth"} | -> 0(5), -> 2(8),
     ///     {"name": "Mars"} | -> 1(8),
     ///     ```
     ///
+    ///     Empty lines are skipped, they don't count as nodes. `edge_weight` is parsed as
+    ///     `Self::Weight`, e.g. `-> 2(-1.5)` for `f64`.
+    ///
+    /// # Errors
+    ///
+    /// Never panics on bad input, a `GraphLoadError` tells which line (and column) is wrong.
+    ///
     /// # Examples
     ///
-    /// ```no_run
+    /// ```
+    /// # use data_structure_implementation_by_rust::graph::undirected_graph::*;
+    /// # #[derive(Debug, serde::Deserialize)]
+    /// # struct Planet {
+    /// #     label: Option<String>,
+    /// # }
     /// match UndirectedGraph::<Planet>::load_from_file("alien_map_1.txt") {
     ///     Ok(graph) => {
     ///         println!("loaded graph: {graph:#?}");
@@ -114,23 +189,595 @@ pub trait Graph<T: Debug + DeserializeOwned> {
     ///
     /// ```
     ///
-    fn load_from_file(graph_filename: &str) -> Result<Self, String>
+    fn load_from_file(graph_filename: &str) -> Result<Self, GraphLoadError<Self::Weight>>
     where
         Self: Sized;
-    // fn add_node(&mut self, node: GraphNode<T>);
+
+    /// Add a node without any edge, get back its `NodeId`
+    fn add_node(&mut self, data: T) -> NodeId;
+
+    /// Connect `a` and `b`. Fails if any of them doesn't exist, or they're already connected
+    /// (use `update_weight` instead).
+    fn add_edge(&mut self, a: NodeId, b: NodeId, weight: Self::Weight) -> Result<(), String>;
+
+    /// Disconnect `a` and `b`, get back the weight of the removed edge.
+    fn remove_edge(&mut self, a: NodeId, b: NodeId) -> Option<Self::Weight>;
+
+    /// Remove the node and all its edges, get back its data.
+    fn remove_node(&mut self, node_index: NodeId) -> Option<T>;
+
+    /// Change the weight of the edge between `a` and `b`, get back the old weight.
+    fn update_weight(&mut self, a: NodeId, b: NodeId, weight: Self::Weight)
+        -> Option<Self::Weight>;
+
+    ///
+    fn node_data_mut(&mut self, node_index: NodeId) -> Option<&mut T>;
+
+    /// How many nodes are in the graph, the removed nodes (and the nodes without data, see
+    /// `with_all_nodes`) don't count.
     fn nodes_len(&self) -> usize;
+
+    /// How many `GraphNodeEdge` are in the graph, an `UndirectedGraph` edge is counted from both
+    /// sides of the edge.
     fn edges_len(&self) -> usize;
-    // fn get_neighbors_by_node(&self, node_index: usize) -> [GraphNode<T>];
+
+    /// The node data, `None` if the node doesn't exist (or doesn't have data).
+    fn node(&self, node_index: NodeId) -> Option<&T>;
+
+    /// All `(neighbor_index, weight, neighbor_data)` of the node, it's empty if the node
+    /// doesn't exist. The neighbor without data is skipped.
+    fn neighbors<'a>(
+        &'a self,
+        node_index: NodeId,
+    ) -> impl Iterator<Item = (NodeId, Self::Weight, &'a T)>
+    where
+        T: 'a;
+
+    /// How many edges the node has, `None` if the node doesn't exist.
+    fn degree(&self, node_index: NodeId) -> Option<usize>;
+
+    ///
+    fn has_edge(&self, a: NodeId, b: NodeId) -> bool;
+
+    /// The weight of the edge between `a` and `b`
+    fn edge_weight(&self, a: NodeId, b: NodeId) -> Option<Self::Weight>;
+
+    /// All `(node_index, node_data)` in the `NodeId` order, the removed nodes and the nodes
+    /// without data are skipped.
+    fn nodes<'a>(&'a self) -> impl Iterator<Item = (NodeId, &'a T)>
+    where
+        T: 'a;
+
+    /// The first node which matches the `predicate`, e.g. find the planet by label:
+    ///
+    /// ```
+    /// # use data_structure_implementation_by_rust::graph::undirected_graph::*;
+    /// # #[derive(Debug, serde::Deserialize)]
+    /// # struct Planet {
+    /// #     label: Option<String>,
+    /// # }
+    /// # let graph = UndirectedGraph::<Planet>::load_from_file("alien_map_1.txt").unwrap();
+    /// let earth = graph.find_node(|planet| planet.label.as_deref() == Some("Earth"));
+    /// assert_eq!(earth.map(|(node_index, _)| node_index), Some(1));
+    /// ```
+    fn find_node<P: FnMut(&T) -> bool>(&self, mut predicate: P) -> Option<(NodeId, &T)> {
+        self.nodes().find(|(_, data)| predicate(data))
+    }
 }
 
+/// What to do if an edge `A -> B` doesn't have the edge `B -> A` back
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum AsymmetricEdgePolicy {
+    // Fail with `GraphLoadError::AsymmetricEdge`
+    #[default]
+    Error,
+    // Add the missing `B -> A` with the same weight
+    Mirror,
+    // Load it as it is, and report it as a warning
+    Warn,
+}
+
+/// What to do if `A -> B` and `B -> A` have different weights
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum MismatchedWeightPolicy {
+    // Fail with `GraphLoadError::MismatchedWeight`
+    #[default]
+    Error,
+    // Both directions use the weight of the edge in the first line
+    UseFirst,
+    // Load it as it is, and report it as a warning
+    Warn,
+}
+
+/// How strict `UndirectedGraph::load_from_file_with_options` is, the `Default` rejects all
+/// asymmetric edges and mismatched weights.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct GraphLoadOptions {
+    pub asymmetric_edges: AsymmetricEdgePolicy,
+    pub mismatched_weights: MismatchedWeightPolicy,
+}
+
+/// An edge and where it is in the graph file, only used during loading.
+#[derive(Debug)]
+struct LoadedEdge<W> {
+    from: usize,
+    // The index in `nodes[from].neighbors`
+    neighbor_index: usize,
+    to: usize,
+    weight: W,
+    line_number: usize,
+    column_number: usize,
+}
+
+///
+/// # `UndirectedGraph`
+///
+/// Each edge is saved in both nodes, `A -> B` in `A` and `B -> A` in `B`, except the self-loop
+/// which is only saved once.
+///
+/// # Index stability
 ///
+/// `remove_node` leaves a tombstone in the node array rather than shifting the nodes after it,
+/// so all `NodeId` (and the `node_index` in all edges) are still valid after removing. The
+/// removed `NodeId` is never reused, a new node always gets a new `NodeId`.
 ///
+/// # Weight
 ///
-pub struct UndirectedGraph<T: Debug + DeserializeOwned> {
-    nodes: Vec<GraphNode<T>>,
+/// `W` is `usize` by default, use a signed or float `Weight` for the negative weights, e.g.
+/// `UndirectedGraph<Planet, i64>`.
+///
+pub struct UndirectedGraph<T: Debug + DeserializeOwned, W: Weight = usize> {
+    nodes: Vec<GraphNode<T, W>>,
+
+    // Tombstones of the removed nodes
+    removed_nodes: BTreeSet<NodeId>,
 }
 
-impl<T: Debug + DeserializeOwned> UndirectedGraph<T> {
+impl<T: Debug + DeserializeOwned, W: Weight> UndirectedGraph<T, W> {
+    /// Same as `load_from_file`, and `options` decides what to do if an edge doesn't have
+    /// the same edge back. Besides the loaded graph, it also gives back the problems which
+    /// are only warned.
+    ///
+    /// ```
+    /// # use data_structure_implementation_by_rust::graph::undirected_graph::*;
+    /// # #[derive(Debug, serde::Deserialize)]
+    /// # struct Planet {
+    /// #     label: Option<String>,
+    /// # }
+    /// let options = GraphLoadOptions {
+    ///     asymmetric_edges: AsymmetricEdgePolicy::Mirror,
+    ///     mismatched_weights: MismatchedWeightPolicy::Warn,
+    /// };
+    /// let (graph, warnings) =
+    ///     UndirectedGraph::<Planet>::load_from_file_with_options("alien_map_1.txt", &options)?;
+    /// assert!(warnings.is_empty());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn load_from_file_with_options(
+        graph_filename: &str,
+        options: &GraphLoadOptions,
+    ) -> Result<(Self, Vec<GraphLoadError<W>>), GraphLoadError<W>> {
+        let (nodes, edge_positions) = read_graph_file::<T, W>(graph_filename)?;
+        let mut loaded_graph = Self::with_all_nodes(nodes);
+
+        let mut loaded_edges = Vec::<LoadedEdge<W>>::with_capacity(edge_positions.len());
+        let mut edge_positions = edge_positions.into_iter();
+        for (from, node) in loaded_graph.nodes.iter().enumerate() {
+            for (neighbor_index, edge) in node.neighbors.iter().enumerate() {
+                let (line_number, column_number) = edge_positions.next().unwrap();
+                loaded_edges.push(LoadedEdge {
+                    from,
+                    neighbor_index,
+                    to: edge.node_index,
+                    weight: edge.weight,
+                    line_number,
+                    column_number,
+                });
+            }
+        }
+
+        let warnings = loaded_graph.validate_symmetry(loaded_edges, options)?;
+        Ok((loaded_graph, warnings))
+    }
+
+    /// Every edge should have the same edge back (with the same weight), fix them or report
+    /// them by the `options`. The problems are handled in the file order.
+    fn validate_symmetry(
+        &mut self,
+        loaded_edges: Vec<LoadedEdge<W>>,
+        options: &GraphLoadOptions,
+    ) -> Result<Vec<GraphLoadError<W>>, GraphLoadError<W>> {
+        // Group the edges by the node pair, `(smaller_node, bigger_node)` as the key. The
+        // edges from the smaller node are `forward_edges`, the others are `backward_edges`.
+        let mut edges_by_node_pair = BTreeMap::<(usize, usize), (Vec<usize>, Vec<usize>)>::new();
+        for (edge_index, edge) in loaded_edges.iter().enumerate() {
+            // A self-loop is its own edge back
+            if edge.from == edge.to {
+                continue;
+            }
+
+            let node_pair = (edge.from.min(edge.to), edge.from.max(edge.to));
+            let (forward_edges, backward_edges) = edges_by_node_pair.entry(node_pair).or_default();
+            if edge.from < edge.to {
+                forward_edges.push(edge_index);
+            } else {
+                backward_edges.push(edge_index);
+            }
+        }
+
+        // `(edge_index, Some(reverse_edge_index))` for the mismatched weights,
+        // `(edge_index, None)` for the asymmetric edges
+        let mut problems = Vec::<(usize, Option<usize>)>::new();
+        for (forward_edges, backward_edges) in edges_by_node_pair.values() {
+            for pair_index in 0..forward_edges.len().max(backward_edges.len()) {
+                match (
+                    forward_edges.get(pair_index),
+                    backward_edges.get(pair_index),
+                ) {
+                    (Some(&forward_edge), Some(&backward_edge)) => {
+                        if loaded_edges[forward_edge].weight != loaded_edges[backward_edge].weight {
+                            problems.push((backward_edge, Some(forward_edge)));
+                        }
+                    }
+                    (Some(&edge_index), None) | (None, Some(&edge_index)) => {
+                        problems.push((edge_index, None))
+                    }
+                    (None, None) => {}
+                }
+            }
+        }
+        problems.sort_unstable();
+
+        let mut warnings = Vec::<GraphLoadError<W>>::new();
+        for (edge_index, reverse_edge_index) in problems {
+            let edge = &loaded_edges[edge_index];
+            match reverse_edge_index {
+                None => {
+                    let problem = GraphLoadError::AsymmetricEdge {
+                        line_number: edge.line_number,
+                        column_number: edge.column_number,
+                        from_node: edge.from,
+                        to_node: edge.to,
+                    };
+                    match options.asymmetric_edges {
+                        AsymmetricEdgePolicy::Error => return Err(problem),
+                        AsymmetricEdgePolicy::Warn => warnings.push(problem),
+                        AsymmetricEdgePolicy::Mirror => {
+                            self.nodes[edge.to].neighbors.push(GraphNodeEdge {
+                                node_index: edge.from,
+                                weight: edge.weight,
+                            });
+                        }
+                    }
+                }
+                Some(reverse_edge_index) => {
+                    let reverse_edge = &loaded_edges[reverse_edge_index];
+                    let problem = GraphLoadError::MismatchedWeight {
+                        line_number: edge.line_number,
+                        column_number: edge.column_number,
+                        from_node: edge.from,
+                        to_node: edge.to,
+                        weight: edge.weight,
+                        reverse_line_number: reverse_edge.line_number,
+                        reverse_weight: reverse_edge.weight,
+                    };
+                    match options.mismatched_weights {
+                        MismatchedWeightPolicy::Error => return Err(problem),
+                        MismatchedWeightPolicy::Warn => warnings.push(problem),
+                        MismatchedWeightPolicy::UseFirst => {
+                            self.nodes[edge.from].neighbors[edge.neighbor_index].weight =
+                                reverse_edge.weight;
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(warnings)
+    }
+
+    /// Save the graph in the same format as `load_from_file`, see `write_to`.
+    ///
+    /// ```
+    /// # use data_structure_implementation_by_rust::graph::undirected_graph::*;
+    /// # #[derive(Debug, serde::Serialize, serde::Deserialize)]
+    /// # struct Planet {
+    /// #     label: Option<String>,
+    /// # }
+    /// # fn planet(label: &str) -> Planet {
+    /// #     Planet {
+    /// #         label: Some(label.to_string()),
+    /// #     }
+    /// # }
+    /// let mut graph = UndirectedGraph::<Planet>::load_from_file("alien_map_1.txt")?;
+    /// let pluto = graph.add_node(planet("Pluto"));
+    /// graph.add_edge(2, pluto, 20)?;
+    /// # let temp_path = std::env::temp_dir().join("save_to_file_doc_example.txt");
+    /// # let graph_filename = temp_path.to_str().unwrap();
+    /// graph.save_to_file(graph_filename)?;
+    /// # assert_eq!(UndirectedGraph::<Planet>::load_from_file(graph_filename)?.nodes_len(), 4);
+    /// # std::fs::remove_file(graph_filename)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn save_to_file(&self, graph_filename: &str) -> io::Result<()>
+    where
+        T: Serialize,
+    {
+        let mut writer = BufWriter::new(File::create(graph_filename)?);
+        self.write_to(&mut writer)?;
+        writer.flush()
+    }
+
+    /// Write one line per node: the node data as the compact JSON, then all its edges, e.g.
+    /// `{"label":"Earth"} | -> 0(5), -> 2(8),`. Loading it back and writing again gives back
+    /// exactly the same bytes.
+    ///
+    /// The removed nodes aren't written, so the `NodeId` of the nodes after them go down by
+    /// the lines they used to take. The nodes without data are removed nodes too (see
+    /// `Graph::with_all_nodes`), so there are always `nodes_len()` lines.
+    ///
+    /// # Errors
+    ///
+    /// The `writer` errors, or the node data can't be serialized as JSON.
+    pub fn write_to<Wr: Write>(&self, writer: Wr) -> io::Result<()>
+    where
+        T: Serialize,
+    {
+        write_graph_file(self, writer)
+    }
+
+    /// Breadth-first traversal from `start`, see `Bfs`
+    pub fn bfs(&self, start: NodeId) -> Bfs<'_, T, Self> {
+        Bfs::new(self, start)
+    }
+
+    /// Depth-first traversal from `start` in pre-order, see `Dfs`
+    pub fn dfs(&self, start: NodeId) -> Dfs<'_, T, Self> {
+        Dfs::new(self, start)
+    }
+
+    /// Depth-first traversal from `start` with both pre-order and post-order events, see
+    /// `DfsEvents`
+    pub fn dfs_events(&self, start: NodeId) -> DfsEvents<'_, T, Self> {
+        DfsEvents::new(self, start)
+    }
+
+    /// The component id of each node, see `ConnectedComponents`
+    pub fn connected_components(&self) -> ConnectedComponents {
+        connected_components(self)
+    }
+
+    ///
+    pub fn component_count(&self) -> usize {
+        connected_components(self).count()
+    }
+
+    /// Whether all nodes are in one component, an empty graph counts as connected.
+    pub fn is_connected(&self) -> bool {
+        match self.nodes().next() {
+            Some((first_node, _)) => Bfs::new(self, first_node).count() == self.nodes().count(),
+            None => true,
+        }
+    }
+
+    /// Whether there is a path between `a` and `b`, `false` if any of them doesn't exist.
+    pub fn are_connected(&self, a: NodeId, b: NodeId) -> bool {
+        are_connected(self, a, b)
+    }
+
+    /// One cycle's nodes if there is any, the last node connects back to the first one. A
+    /// self-loop is a cycle of one node.
+    pub fn find_cycle(&self) -> Option<Vec<NodeId>> {
+        find_cycle(self)
+    }
+
+    ///
+    pub fn has_cycle(&self) -> bool {
+        find_cycle(self).is_some()
+    }
+
+    /// Whether there isn't any cycle, each connected component is a tree.
+    pub fn is_forest(&self) -> bool {
+        !self.has_cycle()
+    }
+
+    /// Whether it's connected and there isn't any cycle, an empty graph isn't a tree.
+    pub fn is_tree(&self) -> bool {
+        self.nodes().next().is_some() && self.is_connected() && self.is_forest()
+    }
+
+    /// Split the nodes into two sides, or give back an odd cycle as the proof that it's not
+    /// possible, see `Bipartition`
+    pub fn bipartition(&self) -> Bipartition {
+        bipartition(self)
+    }
+
+    /// Bridges, articulation points and biconnected components in one pass, see
+    /// `Biconnectivity`
+    pub fn biconnectivity(&self) -> Biconnectivity {
+        biconnectivity(self)
+    }
+
+    /// The edges `(a, b)` (`a < b`) which disconnect the graph after being removed
+    pub fn bridges(&self) -> Vec<(NodeId, NodeId)> {
+        biconnectivity(self).bridges
+    }
+
+    /// The nodes which disconnect the graph after being removed
+    pub fn articulation_points(&self) -> Vec<NodeId> {
+        biconnectivity(self).articulation_points
+    }
+
+    ///
+    pub fn biconnected_components(&self) -> Vec<BiconnectedComponent> {
+        biconnectivity(self).biconnected_components
+    }
+
+    /// The path with the smallest total weight from `from` to `to` (Dijkstra's algorithm),
+    /// `None` if `to` isn't reachable or any of them doesn't exist.
+    pub fn shortest_path(&self, from: NodeId, to: NodeId) -> Option<ShortestPath<W>> {
+        dijkstra(self, from, Some(to), &mut NoFilter).path_to(to)
+    }
+
+    /// The shortest distances (and paths) from `from` to all reachable nodes
+    pub fn shortest_path_tree(&self, from: NodeId) -> ShortestPathTree<W> {
+        dijkstra(self, from, None, &mut NoFilter)
+    }
+
+    /// Same as `shortest_path_tree`, but it works with the negative weights (Bellman-Ford
+    /// algorithm). Fails if any negative cycle is reachable from `from`. Each edge with a
+    /// negative weight is a negative cycle in an `UndirectedGraph`, since it can go back and
+    /// forth.
+    pub fn bellman_ford_shortest_path_tree(
+        &self,
+        from: NodeId,
+    ) -> Result<ShortestPathTree<W>, NegativeCycle> {
+        bellman_ford(self, from)
+    }
+
+    /// The shortest distances (and paths) between every two nodes by Floyd-Warshall
+    /// algorithm, see `AllPairsShortestPaths`. Fails if there is any negative cycle.
+    pub fn floyd_warshall_all_pairs(&self) -> Result<AllPairsShortestPaths<W>, NegativeCycle> {
+        floyd_warshall(self)
+    }
+
+    /// Any cycle with a negative total weight, no matter which nodes can reach it.
+    pub fn find_negative_cycle(&self) -> Option<NegativeCycle> {
+        find_negative_cycle(self)
+    }
+
+    /// A* search from `from` to `to`, `heuristic(node_data, to_data)` estimates the cost from
+    /// a node to `to`. Use `euclidean_distance` or `manhattan_distance` if the node data has
+    /// a position, or `|_, _| 0` to work like Dijkstra's algorithm.
+    pub fn a_star<H>(&self, from: NodeId, to: NodeId, heuristic: H) -> AStarResult<W>
+    where
+        H: FnMut(&T, &T) -> W,
+    {
+        a_star(self, from, to, heuristic, &mut NoFilter)
+    }
+
+    /// The path with the fewest edges from `from` to `to`, only through the nodes and edges
+    /// allowed by the predicates. `node_predicate(node_index, data)` is also checked on `from`
+    /// and `to`, `edge_predicate(from, to, weight)` is checked in the search direction.
+    ///
+    /// It's `PathSearchOutcome::Blocked` rather than `NoPath` when `to` is only reachable by
+    /// going through the filtered out nodes or edges:
+    ///
+    /// ```
+    /// # use data_structure_implementation_by_rust::graph::undirected_graph::*;
+    /// # #[derive(Debug, serde::Deserialize)]
+    /// # #[serde(rename_all = "camelCase")]
+    /// # struct Planet {
+    /// #     is_reachable: bool,
+    /// # }
+    /// # let graph = UndirectedGraph::<Planet>::load_from_file("alien_map_1.txt").unwrap();
+    /// # let (home, mars) = (0, 2);
+    /// let outcome = graph.bfs_path_filtered(
+    ///     home,
+    ///     mars,
+    ///     |_, planet: &Planet| planet.is_reachable,
+    ///     |_, _, weight| weight <= 10,
+    /// );
+    /// assert_eq!(outcome.path().map(|path| path.nodes.len()), Some(3));
+    /// ```
+    pub fn bfs_path_filtered<N, E>(
+        &self,
+        from: NodeId,
+        to: NodeId,
+        node_predicate: N,
+        edge_predicate: E,
+    ) -> PathSearchOutcome<W>
+    where
+        N: FnMut(NodeId, &T) -> bool,
+        E: FnMut(NodeId, NodeId, W) -> bool,
+    {
+        filtered_bfs_path(self, from, to, node_predicate, edge_predicate)
+    }
+
+    /// Same as `shortest_path`, but filtered in the same way as `bfs_path_filtered`
+    pub fn shortest_path_filtered<N, E>(
+        &self,
+        from: NodeId,
+        to: NodeId,
+        node_predicate: N,
+        edge_predicate: E,
+    ) -> PathSearchOutcome<W>
+    where
+        N: FnMut(NodeId, &T) -> bool,
+        E: FnMut(NodeId, NodeId, W) -> bool,
+    {
+        filtered_dijkstra(self, from, to, node_predicate, edge_predicate)
+    }
+
+    /// Same as `a_star`, but filtered in the same way as `bfs_path_filtered`
+    pub fn a_star_filtered<H, N, E>(
+        &self,
+        from: NodeId,
+        to: NodeId,
+        heuristic: H,
+        node_predicate: N,
+        edge_predicate: E,
+    ) -> FilteredAStarResult<W>
+    where
+        H: FnMut(&T, &T) -> W,
+        N: FnMut(NodeId, &T) -> bool,
+        E: FnMut(NodeId, NodeId, W) -> bool,
+    {
+        filtered_a_star(self, from, to, heuristic, node_predicate, edge_predicate)
+    }
+
+    /// The maximum flow from `source` to `sink` (Edmonds-Karp algorithm) and the minimum cut,
+    /// each edge weight is its capacity in both directions. See `MaxFlow`, `None` if any of them
+    /// doesn't exist or `source == sink`.
+    pub fn max_flow(&self, source: NodeId, sink: NodeId) -> Option<MaxFlow<W>>
+    where
+        W: Sub<Output = W>,
+    {
+        edmonds_karp(self, source, sink)
+    }
+
+    /// The minimum spanning forest by Prim's algorithm, see `SpanningForest`
+    pub fn prim_spanning_forest(&self) -> SpanningForest<W> {
+        prim(self)
+    }
+
+    /// The minimum spanning forest by Kruskal's algorithm (with `UnionFind`), it's the same as
+    /// `prim_spanning_forest`.
+    pub fn kruskal_spanning_forest(&self) -> SpanningForest<W> {
+        kruskal(self)
+    }
+
+    /// The node array (with the tombstones) and the removed nodes, for converting to the other
+    /// graph types without changing any `NodeId`.
+    pub(crate) fn into_parts(self) -> (Vec<GraphNode<T, W>>, BTreeSet<NodeId>) {
+        (self.nodes, self.removed_nodes)
+    }
+
+    /// The reverse of `into_parts`, each edge should be saved in both nodes already.
+    pub(crate) fn from_parts(nodes: Vec<GraphNode<T, W>>, removed_nodes: BTreeSet<NodeId>) -> Self {
+        UndirectedGraph {
+            nodes,
+            removed_nodes,
+        }
+    }
+
+    /// Whether the node exists and it's not removed
+    pub fn contains_node(&self, node_index: NodeId) -> bool {
+        node_index < self.nodes.len() && !self.removed_nodes.contains(&node_index)
+    }
+
+    /// The position of the first edge `from -> to` in `nodes[from].neighbors`
+    fn edge_position(&self, from: NodeId, to: NodeId) -> Option<usize> {
+        if !self.contains_node(from) {
+            return None;
+        }
+
+        self.nodes[from]
+            .neighbors
+            .iter()
+            .position(|edge| edge.node_index == to)
+    }
+
     ///
     ///
     ///
@@ -152,7 +799,7 @@ impl<T: Debug + DeserializeOwned> UndirectedGraph<T> {
     }
 }
 
-impl<T: Debug + DeserializeOwned> std::fmt::Debug for UndirectedGraph<T> {
+impl<T: Debug + DeserializeOwned, W: Weight> std::fmt::Debug for UndirectedGraph<T, W> {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         let mut debug_info = f.debug_struct("[ UndirectedGraph ]");
         debug_info.field("nodes_len", &self.nodes_len());
@@ -164,8 +811,13 @@ impl<T: Debug + DeserializeOwned> std::fmt::Debug for UndirectedGraph<T> {
         //
         // Output like a adjacency list
         //
-        for (index, node) in self.nodes.iter().enumerate() {
-            let label = if index == 0 {
+        let live_nodes = self
+            .nodes
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| !self.removed_nodes.contains(index));
+        for (position, (index, node)) in live_nodes.enumerate() {
+            let label = if position == 0 {
                 format!(
                     "\n>>> Nodes in adjacency list format>>>\n\n[{}] {:?}",
                     index, &node.data
@@ -181,147 +833,127 @@ impl<T: Debug + DeserializeOwned> std::fmt::Debug for UndirectedGraph<T> {
     }
 }
 
-impl<T: Debug + DeserializeOwned> Graph<T> for UndirectedGraph<T> {
+impl<T: Debug + DeserializeOwned, W: Weight> Graph<T> for UndirectedGraph<T, W> {
+    type Weight = W;
+
     ///
     ///
     ///
-    fn with_first_node(first_node: GraphNode<T>) -> Self {
-        Self {
-            nodes: vec![first_node],
-        }
+    fn with_first_node(first_node: GraphNode<T, W>) -> Self {
+        Self::with_all_nodes(vec![first_node])
     }
 
     ///
     ///
     ///
-    fn with_all_nodes(nodes: Vec<GraphNode<T>>) -> Self {
-        Self { nodes }
+    fn with_all_nodes(mut nodes: Vec<GraphNode<T, W>>) -> Self {
+        let removed_nodes = remove_nodes_without_data(&mut nodes);
+        Self {
+            nodes,
+            removed_nodes,
+        }
     }
 
-    fn load_from_file(graph_filename: &str) -> Result<Self, String>
+    fn load_from_file(graph_filename: &str) -> Result<Self, GraphLoadError<W>>
     where
         Self: Sized,
     {
-        // The output is wrapped in a Result to allow matching on errors
-        // Returns an Iterator to the Reader of the lines of the file.
-        fn read_lines<P>(filename: P) -> Result<io::Lines<io::BufReader<File>>, std::io::Error>
-        where
-            P: AsRef<Path>,
-        {
-            let file = File::open(filename)?;
-            Ok(io::BufReader::new(file).lines())
+        Self::load_from_file_with_options(graph_filename, &GraphLoadOptions::default())
+            .map(|(graph, _)| graph)
+    }
+
+    ///
+    fn add_node(&mut self, data: T) -> NodeId {
+        self.nodes.push(GraphNode {
+            data: Some(data),
+            neighbors: vec![],
+        });
+        self.nodes.len() - 1
+    }
+
+    ///
+    fn add_edge(&mut self, a: NodeId, b: NodeId, weight: W) -> Result<(), String> {
+        for node_index in [a, b] {
+            if !self.contains_node(node_index) {
+                return Err(format!("Node {} doesn't exist", node_index));
+            }
         }
 
-        #[derive(Debug, Serialize, Deserialize)]
-        #[serde(rename_all = "camelCase")]
-        struct Planet {
-            name: String,
-            draw_color: String,
-            draw_sprite: String,
-            is_reachable: bool,
+        if self.edge_position(a, b).is_some() {
+            return Err(format!("Node {} and {} are already connected", a, b));
         }
 
-        let lines = read_lines(graph_filename);
-        if lines.is_err() {
-            return Err(lines.unwrap_err().to_string());
+        self.nodes[a].neighbors.push(GraphNodeEdge {
+            node_index: b,
+            weight,
+        });
+        if a != b {
+            self.nodes[b].neighbors.push(GraphNodeEdge {
+                node_index: a,
+                weight,
+            });
         }
 
-        let mut loaded_graph = Self { nodes: vec![] };
-
-        for line in lines.unwrap() {
-            if let Ok(node_str) = line {
-                // println!("lines: {node_str}");
-
-                let mut graph_node = GraphNode::<T> {
-                    data: None,
-                    neighbors: vec![],
-                };
-
-                //
-                // Each line has the following format:
-                //
-                // `{...JSON data here} | edges_separated_by_comma`
-                //
-                // Each edge has the following format:
-                //
-                // `-> Connected_node_index(edge_weight)`
-                //
-                // Here is the example:
-                //
-                // {"name": "Alien Home"} |  -> 1(5),
-                // {"name": "Earth"} | -> 0(5), -> 2(8),
-                // {"name": "Mars"} | -> 1(8),
-                //
-                for (index, temp_str) in node_str.split("|").into_iter().enumerate() {
-                    // GraphNode data json
-                    if index == 0 {
-                        graph_node.data = Some(from_str::<T>(temp_str).unwrap());
-                    }
-                    // GraphNodeEdge
-                    else if index == 1 {
-                        let edges = temp_str.split(",");
-                        for edge_str in edges.into_iter() {
-                            if edge_str.trim() == "" {
-                                continue;
-                            }
-                            // println!("edge_str: {edge_str}");
-
-                            let invalid_edge_format = format!("Edge string '{edge_str}' should the following format: '-> Connected_node_index(edge_weight)'");
-                            let after_arrow_pos = edge_str.find("-> ");
-                            let left_parentheses_pos = edge_str.find('(');
-                            let right_parentheses_pos = edge_str.find(')');
-
-                            if after_arrow_pos.is_none()
-                                || left_parentheses_pos.is_none()
-                                || right_parentheses_pos.is_none()
-                            {
-                                return Err(invalid_edge_format);
-                            }
-
-                            let connected_node_index = &edge_str
-                                [after_arrow_pos.unwrap() + 3..left_parentheses_pos.unwrap()]
-                                .parse::<usize>();
-                            // println!("connected_node_index {connected_node_index:?}");
-
-                            if connected_node_index.is_err() {
-                                return Err(invalid_edge_format);
-                            }
-
-                            let edge_weight = &edge_str
-                                [left_parentheses_pos.unwrap() + 1..right_parentheses_pos.unwrap()]
-                                .parse::<usize>();
-                            // println!("edge_weight: {edge_weight:?}");
-
-                            if edge_weight.is_err() {
-                                return Err(invalid_edge_format);
-                            }
-
-                            graph_node.neighbors.push(GraphNodeEdge {
-                                node_index: *connected_node_index.as_ref().unwrap(),
-                                weight: *edge_weight.as_ref().unwrap(),
-                            });
-                        }
-                    }
-                }
+        Ok(())
+    }
 
-                loaded_graph.nodes.push(graph_node);
+    ///
+    fn remove_edge(&mut self, a: NodeId, b: NodeId) -> Option<W> {
+        let position = self.edge_position(a, b)?;
+        let removed_edge = self.nodes[a].neighbors.remove(position);
+        if a != b {
+            if let Some(reverse_position) = self.edge_position(b, a) {
+                self.nodes[b].neighbors.remove(reverse_position);
             }
         }
 
-        Ok(loaded_graph)
+        Some(removed_edge.weight)
     }
 
-    // ///
-    // ///
-    // fn add_node(&mut self, node: GraphNode<T>) {
-    //    self. nodes.push(node);
-    // }
+    ///
+    fn remove_node(&mut self, node_index: NodeId) -> Option<T> {
+        if !self.contains_node(node_index) {
+            return None;
+        }
+
+        let removed_edges = std::mem::take(&mut self.nodes[node_index].neighbors);
+        for edge in removed_edges {
+            if edge.node_index != node_index {
+                self.nodes[edge.node_index]
+                    .neighbors
+                    .retain(|edge| edge.node_index != node_index);
+            }
+        }
+
+        self.removed_nodes.insert(node_index);
+        self.nodes[node_index].data.take()
+    }
+
+    ///
+    fn update_weight(&mut self, a: NodeId, b: NodeId, weight: W) -> Option<W> {
+        let position = self.edge_position(a, b)?;
+        let old_weight = std::mem::replace(&mut self.nodes[a].neighbors[position].weight, weight);
+        if let Some(reverse_position) = self.edge_position(b, a) {
+            self.nodes[b].neighbors[reverse_position].weight = weight;
+        }
+
+        Some(old_weight)
+    }
+
+    ///
+    fn node_data_mut(&mut self, node_index: NodeId) -> Option<&mut T> {
+        if !self.contains_node(node_index) {
+            return None;
+        }
+
+        self.nodes[node_index].data.as_mut()
+    }
 
     ///
     ///
     ///
     fn nodes_len(&self) -> usize {
-        self.nodes.len()
+        self.nodes.len() - self.removed_nodes.len()
     }
 
     ///
@@ -333,10 +965,58 @@ impl<T: Debug + DeserializeOwned> Graph<T> for UndirectedGraph<T> {
             .fold(0, |acc, ele| acc + ele.neighbors.len())
     }
 
-    // ///
-    // ///
-    // ///
-    // fn get_neighbors_by_node(&self, node_index: usize) -> [GraphNode<T>] {
-    //     let edges = self.nodes[node_index];
-    // }
+    ///
+    fn node(&self, node_index: NodeId) -> Option<&T> {
+        if !self.contains_node(node_index) {
+            return None;
+        }
+
+        self.nodes[node_index].data.as_ref()
+    }
+
+    ///
+    fn neighbors<'a>(&'a self, node_index: NodeId) -> impl Iterator<Item = (NodeId, W, &'a T)>
+    where
+        T: 'a,
+    {
+        let edges = if self.contains_node(node_index) {
+            &self.nodes[node_index].neighbors[..]
+        } else {
+            &[]
+        };
+
+        edges.iter().filter_map(move |edge| {
+            self.node(edge.node_index)
+                .map(|data| (edge.node_index, edge.weight, data))
+        })
+    }
+
+    ///
+    fn degree(&self, node_index: NodeId) -> Option<usize> {
+        if !self.contains_node(node_index) {
+            return None;
+        }
+
+        Some(self.nodes[node_index].neighbors.len())
+    }
+
+    ///
+    fn has_edge(&self, a: NodeId, b: NodeId) -> bool {
+        self.edge_position(a, b).is_some()
+    }
+
+    ///
+    fn edge_weight(&self, a: NodeId, b: NodeId) -> Option<W> {
+        self.edge_position(a, b)
+            .map(|position| self.nodes[a].neighbors[position].weight)
+    }
+
+    ///
+    fn nodes<'a>(&'a self) -> impl Iterator<Item = (NodeId, &'a T)>
+    where
+        T: 'a,
+    {
+        (0..self.nodes.len())
+            .filter_map(move |node_index| self.node(node_index).map(|data| (node_index, data)))
+    }
 }
@@ -0,0 +1,141 @@
+use std::cmp::Ordering;
+use std::fmt::{Debug, Display};
+use std::str::FromStr;
+
+///
+/// # `Weight`
+///
+/// The edge weight of all graph types, it's implemented for all primitive integers (signed or
+/// unsigned) and floats. `usize` is the default weight of all graph types.
+///
+/// `FromStr` parses the weight in the graph file, e.g. `-> 1(-2)` for `i64` or `-> 1(0.5)` for
+/// `f64`. `Display` prints it in the same way.
+///
+/// The negative weights only work with `bellman_ford` and `floyd_warshall`, the other searches
+/// expect all weights to be `>= zero()`.
+///
+pub trait Weight: Copy + Debug + Display + PartialOrd + FromStr {
+    /// The cost of an empty path
+    fn zero() -> Self;
+
+    /// `None` if the sum is out of range (or `NaN` for the floats)
+    fn checked_add(self, other: Self) -> Option<Self>;
+
+    /// The sum, or the closest value in range if it's out of range
+    fn saturating_add(self, other: Self) -> Self;
+
+    /// A total order which agrees with `PartialOrd`, the floats use `f64::total_cmp`.
+    fn total_cmp(&self, other: &Self) -> Ordering;
+}
+
+macro_rules! impl_integer_weight {
+    ($($integer:ty),*) => {
+        $(
+            ///
+            impl Weight for $integer {
+                fn zero() -> Self {
+                    0
+                }
+
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    <$integer>::checked_add(self, other)
+                }
+
+                fn saturating_add(self, other: Self) -> Self {
+                    <$integer>::saturating_add(self, other)
+                }
+
+                fn total_cmp(&self, other: &Self) -> Ordering {
+                    self.cmp(other)
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_float_weight {
+    ($($float:ty),*) => {
+        $(
+            ///
+            impl Weight for $float {
+                fn zero() -> Self {
+                    0.0
+                }
+
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    let sum = self + other;
+                    if sum.is_nan() {
+                        None
+                    } else {
+                        Some(sum)
+                    }
+                }
+
+                fn saturating_add(self, other: Self) -> Self {
+                    self + other
+                }
+
+                fn total_cmp(&self, other: &Self) -> Ordering {
+                    <$float>::total_cmp(self, other)
+                }
+            }
+        )*
+    };
+}
+
+impl_integer_weight!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+impl_float_weight!(f32, f64);
+
+/// `Ord` by `Weight::total_cmp`, so the weights can be in a `BinaryHeap` or a sorting key.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct OrderedWeight<W>(pub(crate) W);
+
+///
+impl<W: Weight> PartialEq for OrderedWeight<W> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+///
+impl<W: Weight> Eq for OrderedWeight<W> {}
+
+///
+impl<W: Weight> PartialOrd for OrderedWeight<W> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+///
+impl<W: Weight> Ord for OrderedWeight<W> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checked_add_should_fail_out_of_range() {
+        assert_eq!(Weight::checked_add(-3i64, 5), Some(2));
+        assert_eq!(Weight::checked_add(usize::MAX, 1), None);
+        assert_eq!(Weight::checked_add(0.5f64, -1.0), Some(-0.5));
+        assert_eq!(Weight::checked_add(f64::INFINITY, f64::NEG_INFINITY), None);
+    }
+
+    #[test]
+    fn ordered_weight_should_be_a_total_order() {
+        let mut weights = vec![2.5f64, -1.0, f64::NAN, 0.0]
+            .into_iter()
+            .map(OrderedWeight)
+            .collect::<Vec<_>>();
+        weights.sort_unstable();
+
+        let sorted_weights = weights.iter().map(|weight| weight.0).collect::<Vec<_>>();
+        assert_eq!(sorted_weights[..3], [-1.0, 0.0, 2.5]);
+        assert!(sorted_weights[3].is_nan());
+    }
+}
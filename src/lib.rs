@@ -1,6 +1,16 @@
+// The crate documents most items with a bare `///` and keeps `stack::stack`
+// next to its siblings, both on purpose.
+#![allow(
+    clippy::empty_docs,
+    clippy::module_inception,
+    clippy::doc_overindented_list_items,
+    clippy::new_without_default
+)]
+
 pub mod linked_list;
 pub mod queue;
 pub mod stack;
+pub mod union_find;
 pub mod graph;
 
 // #[cfg(test)]
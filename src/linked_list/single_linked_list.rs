@@ -44,6 +44,58 @@ impl<T: Debug + PartialEq> Debug for SingleLinkedList<T> {
     }
 }
 
+/// Clone all nodes into a new list, the cloned list doesn't share any node with `self`.
+impl<T: Debug + PartialEq + Clone> Clone for SingleLinkedList<T> {
+    fn clone(&self) -> Self {
+        let mut cloned_list = SingleLinkedList::<T>::new();
+        let mut current_node: Option<&NonNull<Node<T>>> = self.head.as_ref();
+
+        while let Some(ptr_to_node) = current_node {
+            let node_ref = unsafe { &*ptr_to_node.as_ptr() };
+            cloned_list.append(node_ref.data.clone());
+
+            // Update `current_node` to next
+            current_node = node_ref.next.as_ref();
+        }
+
+        cloned_list
+    }
+}
+
+/// Two lists are equal when they have the same data in the same order.
+impl<T: Debug + PartialEq> PartialEq for SingleLinkedList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        if self.size != other.size {
+            return false;
+        }
+
+        let mut current_node: Option<&NonNull<Node<T>>> = self.head.as_ref();
+        let mut other_current_node: Option<&NonNull<Node<T>>> = other.head.as_ref();
+
+        while let (Some(ptr_to_node), Some(ptr_to_other_node)) = (current_node, other_current_node)
+        {
+            let node_ref = unsafe { &*ptr_to_node.as_ptr() };
+            let other_node_ref = unsafe { &*ptr_to_other_node.as_ptr() };
+            if node_ref.data != other_node_ref.data {
+                return false;
+            }
+
+            // Update both `current_node` to next
+            current_node = node_ref.next.as_ref();
+            other_current_node = other_node_ref.next.as_ref();
+        }
+
+        true
+    }
+}
+
+/// Free all nodes, they're created by `Box::leak` and only `pop_head`/`pop_tail` give them back.
+impl<T: Debug + PartialEq> Drop for SingleLinkedList<T> {
+    fn drop(&mut self) {
+        while self.pop_head().is_some() {}
+    }
+}
+
 ///
 impl<T: Debug + PartialEq> SingleLinkedList<T> {
     ///
@@ -84,7 +136,7 @@ impl<T: Debug + PartialEq> SingleLinkedList<T> {
 
     ///
     pub fn pop_head(&mut self) -> Option<T> {
-        if self.head.is_none() || self.tail.is_none() || self.size <= 0 {
+        if self.head.is_none() || self.tail.is_none() || self.size == 0 {
             return None;
         }
 
@@ -109,7 +161,7 @@ impl<T: Debug + PartialEq> SingleLinkedList<T> {
 
     ///
     pub fn pop_tail(&mut self) -> Option<T> {
-        if self.tail.is_none() || self.head.is_none() || self.size <= 0 {
+        if self.tail.is_none() || self.head.is_none() || self.size == 0 {
             return None;
         }
 
@@ -168,11 +220,7 @@ impl<T: Debug + PartialEq> SingleLinkedList<T> {
     /// `peek_tail` works like `pop_tail`, but it returns the immutable reference to the last node
     /// data rather than consume it.
     pub fn peek_tail(&mut self) -> Option<&T> {
-        if self.tail.is_none() {
-            return None;
-        }
-
-        let ptr_to_tail_node: *mut Node<T> = self.tail.as_ref().unwrap().as_ptr();
+        let ptr_to_tail_node: *mut Node<T> = self.tail.as_ref()?.as_ptr();
         Some(unsafe { &(*ptr_to_tail_node).data })
     }
 
@@ -282,11 +330,7 @@ impl<T: Debug + PartialEq> SingleLinkedList<T> {
             current_node = node_ref.next.as_ref();
         }
 
-        println!(
-            "{}{}",
-            format!("({} elements): ", self.size),
-            list_content.join(" --> ")
-        );
+        println!("({} elements): {}", self.size, list_content.join(" --> "));
     }
 
     /// The content string looks like "xxx ---> yyy", or "empty list".
@@ -309,11 +353,7 @@ impl<T: Debug + PartialEq> SingleLinkedList<T> {
             current_node = node_ref.next.as_ref();
         }
 
-        format!(
-            "{}{}",
-            format!("({} elements): ", self.size),
-            list_content.join(" --> ")
-        )
+        format!("({} elements): {}", self.size, list_content.join(" --> "))
     }
 }
 
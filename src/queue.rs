@@ -14,7 +14,7 @@ pub struct Queue<T: Clone + fmt::Debug> {
 ///
 impl<T: Clone + fmt::Debug + PartialEq> Queue<T> {
     ///
-    fn new() -> Self {
+    pub fn new() -> Self {
         Queue {
             size: 0usize,
             // We don't need the access backwards, that's why I pick 
@@ -24,12 +24,12 @@ impl<T: Clone + fmt::Debug + PartialEq> Queue<T> {
     }
 
     ///
-    fn len(&self) -> usize {
+    pub fn len(&self) -> usize {
         self.size
     }
 
     ///
-    fn dequeue(&mut self) -> Option<T> {
+    pub fn dequeue(&mut self) -> Option<T> {
         let result = self.inner_list.pop_front();
         if result.is_some() {
             self.size -= 1;
@@ -39,13 +39,13 @@ impl<T: Clone + fmt::Debug + PartialEq> Queue<T> {
     }
 
     ///
-    fn enqueue(&mut self, data: T) {
+    pub fn enqueue(&mut self, data: T) {
         self.inner_list.push_back(data);
         self.size += 1;
     }
 
     ///
-    fn is_empty(&self) -> bool {
+    pub fn is_empty(&self) -> bool {
         self.size == 0
     }
 
@@ -1,4 +1,17 @@
 use crate::stack::stack::Stack;
+use diagnostic::{Diagnostic, DiagnosticKind, Severity, Span};
+use language_profile::LanguageProfile;
+use serde::Serialize;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+use unicode_segmentation::UnicodeSegmentation;
+
+pub mod auto_fix;
+pub mod diagnostic;
+pub mod incremental;
+pub mod language_profile;
+pub mod pair_index;
 
 ///
 #[derive(Debug, PartialEq)]
@@ -20,49 +33,190 @@ pub enum TokenType {
     Unknown(char),
 }
 
-#[derive(Debug, PartialEq)]
-enum NeedToPairingResult {
-    Yes(TokenType),
-    No,
+/// Where a token sits in the source code, always against the original (untrimmed) source.
+///
+/// Both `line_number` and `column_number` are start from `1`, `byte_offset` is start from `0`
+/// and counted from the beginning of the source.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize)]
+pub struct TokenPosition {
+    pub line_number: usize,
+    pub column_number: usize,
+    pub byte_offset: usize,
+}
+
+/// How to count the `column_number`
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ColumnUnit {
+    // Each `char` is a column
+    UnicodeScalar,
+    // Each user-perceived character is a column, e.g. `é` written as `e` + `\u{301}`
+    Grapheme,
 }
 
 ///
 #[derive(Debug, PartialEq, Clone)]
 struct PairToken {
     token: char,
-    line_number: usize,
-    column_number: usize,
+    position: TokenPosition,
+}
+
+/// A single line with the position of each char
+#[derive(Debug)]
+struct SourceLine {
+    chars: Vec<char>,
+    positions: Vec<TokenPosition>,
 }
 
 ///
-#[derive(Debug, PartialEq)]
+impl SourceLine {
+    ///
+    fn new(
+        line: &str,
+        line_number: usize,
+        line_byte_offset: usize,
+        column_unit: ColumnUnit,
+    ) -> Self {
+        let mut chars = Vec::<char>::new();
+        let mut positions = Vec::<TokenPosition>::new();
+
+        match column_unit {
+            ColumnUnit::UnicodeScalar => {
+                for (column_index, (byte_index, c)) in line.char_indices().enumerate() {
+                    chars.push(c);
+                    positions.push(TokenPosition {
+                        line_number,
+                        column_number: column_index + 1,
+                        byte_offset: line_byte_offset + byte_index,
+                    });
+                }
+            }
+            ColumnUnit::Grapheme => {
+                for (column_index, (byte_index, grapheme)) in
+                    line.grapheme_indices(true).enumerate()
+                {
+                    for (char_byte_index, c) in grapheme.char_indices() {
+                        chars.push(c);
+                        positions.push(TokenPosition {
+                            line_number,
+                            column_number: column_index + 1,
+                            byte_offset: line_byte_offset + byte_index + char_byte_index,
+                        });
+                    }
+                }
+            }
+        }
+
+        SourceLine { chars, positions }
+    }
+}
+
+/// Where the check is standing when moving to the next line, as string literals
+/// and block comments can go across multiple lines.
+#[derive(Debug, PartialEq, Clone)]
+enum ScanMode {
+    Code,
+    InString {
+        delimiter: String,
+        multiline: bool,
+        position: TokenPosition,
+    },
+    InRawString {
+        hashes: usize,
+        position: TokenPosition,
+    },
+    InBlockComment {
+        opening: String,
+        closing: String,
+        depth: usize,
+        position: TokenPosition,
+    },
+}
+
+///
+#[derive(Debug, PartialEq, Clone)]
 pub enum CodeTokenValidateResult {
     Passed,
-    Fail(usize, usize, String),
+    Fail(Diagnostic),
+}
+
+/// Everything changes during a single check. It's created for each check, that's why the
+/// same validator can be reused for checking many sources.
+#[derive(Debug, PartialEq, Clone)]
+struct ScanState {
+    token_stack: Stack<PairToken>,
+    mode: ScanMode,
+
+    // All `(opening, closing)` pairs found so far, only recorded when building a `PairIndex`
+    matched_pairs: Option<Vec<(PairToken, PairToken)>>,
+}
+
+///
+impl ScanState {
+    ///
+    fn new() -> Self {
+        ScanState {
+            token_stack: Stack::<PairToken>::new(),
+            mode: ScanMode::Code,
+            matched_pairs: None,
+        }
+    }
+
+    /// Same as `new()`, but also records all matched pairs
+    fn recording_pairs() -> Self {
+        ScanState {
+            matched_pairs: Some(vec![]),
+            ..Self::new()
+        }
+    }
+
+    ///
+    fn record_pair(&mut self, opening: PairToken, closing: PairToken) {
+        if let Some(matched_pairs) = self.matched_pairs.as_mut() {
+            matched_pairs.push((opening, closing));
+        }
+    }
 }
 
 /// Use `Stack` to validate all paired tokens in any source code.
 #[derive(Debug)]
 pub struct CodeTokenPairingValidator {
-    token_stack: Stack<PairToken>,
+    profile: LanguageProfile,
+    column_unit: ColumnUnit,
 }
 
 ///
 impl CodeTokenPairingValidator {
-    ///
+    /// Only check `{}`, `[]` and `()`, see `LanguageProfile::default()`.
     pub fn new() -> Self {
+        Self::with_profile(LanguageProfile::default())
+    }
+
+    ///
+    pub fn with_profile(profile: LanguageProfile) -> Self {
         CodeTokenPairingValidator {
-            token_stack: Stack::<PairToken>::new(),
+            profile,
+            column_unit: ColumnUnit::UnicodeScalar,
         }
     }
 
+    /// Count `column_number` by `column_unit`, the default is `ColumnUnit::UnicodeScalar`.
+    pub fn with_column_unit(mut self, column_unit: ColumnUnit) -> Self {
+        self.column_unit = column_unit;
+        self
+    }
+
+    ///
+    pub fn profile(&self) -> &LanguageProfile {
+        &self.profile
+    }
+
     ///
     fn get_token_by_char(char_to_check: char) -> TokenType {
         match char_to_check {
             '{' => TokenType::Braces(char_to_check),
             '}' => TokenType::ReverseBraces(char_to_check),
             '[' => TokenType::Brackets(char_to_check),
-            ']' => TokenType::ReverseBraces(char_to_check),
+            ']' => TokenType::ReverseBrackets(char_to_check),
             '(' => TokenType::Parentheses(char_to_check),
             ')' => TokenType::ReverseParentheses(char_to_check),
             '<' => TokenType::Arrows(char_to_check),
@@ -78,204 +232,516 @@ impl CodeTokenPairingValidator {
     }
 
     ///
-    fn need_to_pairing(token: TokenType) -> NeedToPairingResult {
-        match token {
-            TokenType::Braces(_) => NeedToPairingResult::Yes(token),
-            TokenType::ReverseBraces(_) => NeedToPairingResult::Yes(token),
-            TokenType::Brackets(_) => NeedToPairingResult::Yes(token),
-            TokenType::ReverseBrackets(_) => NeedToPairingResult::Yes(token),
-            TokenType::Parentheses(_) => NeedToPairingResult::Yes(token),
-            TokenType::ReverseParentheses(_) => NeedToPairingResult::Yes(token),
-            TokenType::Arrows(_) => NeedToPairingResult::Yes(token),
-            TokenType::ReverseArrows(_) => NeedToPairingResult::Yes(token),
-            TokenType::Quotes(_) => NeedToPairingResult::Yes(token),
-            TokenType::DoubleQuotes(_) => NeedToPairingResult::Yes(token),
-            _ => NeedToPairingResult::No,
+    fn is_identifier_char(char_to_check: char) -> bool {
+        char_to_check.is_alphanumeric() || char_to_check == '_'
+    }
+
+    /// Whether `pattern` appears in `chars` at `index`
+    fn starts_with_at(chars: &[char], index: usize, pattern: &str) -> bool {
+        let mut pattern_chars = pattern.chars();
+        let mut current_index = index;
+        loop {
+            match pattern_chars.next() {
+                None => return true,
+                Some(c) if chars.get(current_index) == Some(&c) => current_index += 1,
+                Some(_) => return false,
+            }
+        }
+    }
+
+    ///
+    fn span_of(start: TokenPosition, token: &str) -> Span {
+        Span {
+            start,
+            byte_len: token.len(),
+        }
+    }
+
+    /// A string literal, raw string or block comment which never ends
+    fn unterminated_result(
+        kind: DiagnosticKind,
+        token: String,
+        pairing_token: String,
+        position: TokenPosition,
+    ) -> CodeTokenValidateResult {
+        CodeTokenValidateResult::Fail(Diagnostic {
+            kind,
+            severity: Severity::Error,
+            primary_span: Self::span_of(position, &token),
+            related_spans: vec![],
+            expected: Some(pairing_token.clone()),
+            found: None,
+            token,
+            pairing_token,
+        })
+    }
+
+    /// Get back `(hashes, token_len)` if a raw string (`r"`, `r#"`, `br##"`...) starts at
+    /// `index`.
+    fn raw_string_start(chars: &[char], index: usize) -> Option<(usize, usize)> {
+        if chars[index] != 'r' {
+            return None;
+        }
+
+        // `r` or `br` has to be the start of a token, otherwise it's part of an identifier
+        let prefix_start = if index > 0 && chars[index - 1] == 'b' {
+            index - 1
+        } else {
+            index
+        };
+        if prefix_start > 0 && Self::is_identifier_char(chars[prefix_start - 1]) {
+            return None;
+        }
+
+        let hashes = chars[index + 1..].iter().take_while(|c| **c == '#').count();
+        if chars.get(index + 1 + hashes) == Some(&'"') {
+            Some((hashes, hashes + 2))
+        } else {
+            None
+        }
+    }
+
+    /// `'` can be a char literal (`'a'`, `'\n'`, `'\u{1F600}'`) or a lifetime (`'a`), return the
+    /// count of chars need to be skipped.
+    fn char_literal_len(&self, chars: &[char], index: usize) -> usize {
+        match chars.get(index + 1) {
+            Some(c) if Some(*c) == self.profile.escape_char => chars
+                .get(index + 3..)
+                .and_then(|rest| rest.iter().position(|c| *c == '\''))
+                .map(|pos| pos + 4)
+                .unwrap_or(1),
+            Some(_) if chars.get(index + 2) == Some(&'\'') => 3,
+            _ => 1,
         }
     }
 
     /// This function should only be called for dealing with the `reverse` pairing case!!!
-    /// For example, like `)`, `]`, `}`, `'` and `"`
+    /// For example, like `)`, `]` and `}`
     ///
     /// Plz do not use it for the `non-reverse` check!!!
     fn reverse_pairing_token_check(
-        &mut self,
-        token_type: TokenType,
-        line_num: usize,
-        col_num: usize,
+        &self,
+        state: &mut ScanState,
+        current_token: char,
+        position: TokenPosition,
     ) -> CodeTokenValidateResult {
-        let mut failed = false;
-        let current_checking_token: char;
-        let mut missing_checking_token: char = ' ';
-        match token_type {
-            TokenType::ReverseBraces(current_token)
-            | TokenType::ReverseBrackets(current_token)
-            | TokenType::ReverseParentheses(current_token)
-            | TokenType::Quotes(current_token)
-            | TokenType::DoubleQuotes(current_token) => {
-                current_checking_token = current_token;
-
-                #[cfg(feature = "enable_debug_code_token_pairing")]
-                {
-                    println!(
-                        "\nreverse_pairing_token_check -> token_stack:\n {:#?}",
-                        &self.token_stack
+        let missing_checking_token = self.profile.opening_token_of(current_token).unwrap();
+
+        #[cfg(feature = "enable_debug_code_token_pairing")]
+        {
+            println!(
+                "\nreverse_pairing_token_check -> token_stack:\n {:#?}",
+                state.token_stack
+            );
+            println!("current_token: {}", current_token);
+        }
+
+        // A `<` was only guessed as a generic bracket, it's a comparison operator if the
+        // pairing `>` never comes before the outer closing token.
+        if self.profile.generic_angle_brackets {
+            while state.token_stack.peek().map(|t| t.token) == Some('<') {
+                state.token_stack.pop();
+            }
+        }
+
+        let pop_token = state.token_stack.pop();
+
+        #[cfg(feature = "enable_debug_code_token_pairing")]
+        {
+            println!("pop_token: {:#?}", pop_token);
+            println!("token_stack after pop:\n {:#?}", state.token_stack);
+        }
+
+        let (kind, related_spans, expected) = match pop_token {
+            Some(pop_token) if pop_token.token == missing_checking_token => {
+                state.record_pair(
+                    pop_token,
+                    PairToken {
+                        token: current_token,
+                        position,
+                    },
+                );
+                return CodeTokenValidateResult::Passed;
+            }
+            Some(pop_token) => (
+                DiagnosticKind::Mismatch,
+                vec![Self::span_of(
+                    pop_token.position,
+                    &pop_token.token.to_string(),
+                )],
+                self.profile
+                    .closing_token_of(pop_token.token)
+                    .map(|c| c.to_string()),
+            ),
+            None => (DiagnosticKind::UnexpectedCloser, vec![], None),
+        };
+
+        CodeTokenValidateResult::Fail(Diagnostic {
+            kind,
+            severity: Severity::Error,
+            token: current_token.to_string(),
+            pairing_token: missing_checking_token.to_string(),
+            primary_span: Self::span_of(position, &current_token.to_string()),
+            related_spans,
+            expected,
+            found: Some(current_token.to_string()),
+        })
+    }
+
+    /// `<` opens a generic only right after an identifier or `::` (`Vec<u8>`, `::<T>`), and `>`
+    /// closes it only when it's not part of `->` or `=>`.
+    fn generic_angle_bracket_check(state: &mut ScanState, line: &SourceLine, index: usize) {
+        let chars = &line.chars;
+        let previous_char = index.checked_sub(1).map(|i| chars[i]);
+        match Self::get_token_by_char(chars[index]) {
+            TokenType::Arrows(c) => {
+                let after_identifier =
+                    matches!(previous_char, Some(p) if Self::is_identifier_char(p) || p == ':');
+                let is_operator = matches!(chars.get(index + 1), Some('<') | Some('='));
+                if after_identifier && !is_operator {
+                    state.token_stack.push(PairToken {
+                        token: c,
+                        position: line.positions[index],
+                    });
+                }
+            }
+            TokenType::ReverseArrows(c) => {
+                let is_operator = matches!(previous_char, Some('-') | Some('='));
+                if !is_operator && state.token_stack.peek().map(|t| t.token) == Some('<') {
+                    let opening = state.token_stack.pop().unwrap();
+                    state.record_pair(
+                        opening,
+                        PairToken {
+                            token: c,
+                            position: line.positions[index],
+                        },
                     );
-                    println!("current_token: {}", current_token);
                 }
+            }
+            _ => {}
+        }
+    }
 
-                if self.token_stack.is_empty() {
-                    failed = true;
-                } else {
-                    let pop_token = self.token_stack.pop().unwrap();
+    /// Check a single line, `state.mode` carries the string literal or block comment state to
+    /// the next line.
+    fn scan_line(&self, line: &SourceLine, state: &mut ScanState) -> CodeTokenValidateResult {
+        let chars = &line.chars;
+        let mut index = 0;
+        while index < chars.len() {
+            let current_char = chars[index];
 
-                    #[cfg(feature = "enable_debug_code_token_pairing")]
-                    println!("pop_token: {:#?}", pop_token);
-
-                    if current_token == ')' && pop_token.token != '(' {
-                        failed = true;
-                        missing_checking_token = '(';
-                    } else if current_token == ']' && pop_token.token != '[' {
-                        failed = true;
-                        missing_checking_token = '[';
-                    } else if current_token == '}' && pop_token.token != '{' {
-                        failed = true;
-                        missing_checking_token = '{';
-                    } else if current_token == '"' && pop_token.token != '"' {
-                        failed = true;
-                        missing_checking_token = '"';
+            match &mut state.mode {
+                ScanMode::InString { delimiter, .. } => {
+                    if Some(current_char) == self.profile.escape_char {
+                        index += 2;
+                    } else if Self::starts_with_at(chars, index, delimiter) {
+                        index += delimiter.chars().count();
+                        state.mode = ScanMode::Code;
+                    } else {
+                        index += 1;
+                    }
+                    continue;
+                }
+                ScanMode::InRawString { hashes, .. } => {
+                    let hashes = *hashes;
+                    if current_char == '"'
+                        && chars[index + 1..].iter().take_while(|c| **c == '#').count() >= hashes
+                    {
+                        index += hashes + 1;
+                        state.mode = ScanMode::Code;
+                    } else {
+                        index += 1;
                     }
+                    continue;
+                }
+                ScanMode::InBlockComment {
+                    opening,
+                    closing,
+                    depth,
+                    ..
+                } => {
+                    if Self::starts_with_at(chars, index, closing) {
+                        index += closing.chars().count();
+                        *depth -= 1;
+                        if *depth == 0 {
+                            state.mode = ScanMode::Code;
+                        }
+                    } else if self.profile.nested_block_comments
+                        && Self::starts_with_at(chars, index, opening)
+                    {
+                        index += opening.chars().count();
+                        *depth += 1;
+                    } else {
+                        index += 1;
+                    }
+                    continue;
+                }
+                ScanMode::Code => {}
+            }
+
+            // Line comment, skip the rest of the line
+            if self
+                .profile
+                .line_comments
+                .iter()
+                .any(|prefix| Self::starts_with_at(chars, index, prefix))
+            {
+                break;
+            }
+
+            if let Some((opening, closing)) = self
+                .profile
+                .block_comments
+                .iter()
+                .find(|(opening, _)| Self::starts_with_at(chars, index, opening))
+            {
+                state.mode = ScanMode::InBlockComment {
+                    opening: opening.clone(),
+                    closing: closing.clone(),
+                    depth: 1,
+                    position: line.positions[index],
+                };
+                index += opening.chars().count();
+                continue;
+            }
+
+            if self.profile.raw_strings {
+                if let Some((hashes, token_len)) = Self::raw_string_start(chars, index) {
+                    state.mode = ScanMode::InRawString {
+                        hashes,
+                        position: line.positions[index],
+                    };
+                    index += token_len;
+                    continue;
                 }
+            }
+
+            // The longest delimiter wins, so `"""` won't be treated as an empty `""` string.
+            if let Some(string_delimiter) = self
+                .profile
+                .string_delimiters
+                .iter()
+                .filter(|d| Self::starts_with_at(chars, index, &d.delimiter))
+                .max_by_key(|d| d.delimiter.len())
+            {
+                state.mode = ScanMode::InString {
+                    delimiter: string_delimiter.delimiter.clone(),
+                    multiline: string_delimiter.multiline,
+                    position: line.positions[index],
+                };
+                index += string_delimiter.delimiter.chars().count();
+                continue;
+            }
 
-                #[cfg(feature = "enable_debug_code_token_pairing")]
-                println!("token_stack after pop:\n {:#?}", &self.token_stack);
+            if self.profile.char_literals && current_char == '\'' {
+                index += self.char_literal_len(chars, index);
+                continue;
             }
-            _ => {
-                unreachable!();
+
+            if self.profile.generic_angle_brackets && (current_char == '<' || current_char == '>') {
+                Self::generic_angle_bracket_check(state, line, index);
+            } else if self.profile.closing_token_of(current_char).is_some() {
+                state.token_stack.push(PairToken {
+                    token: current_char,
+                    position: line.positions[index],
+                });
+            } else if self.profile.opening_token_of(current_char).is_some() {
+                let temp_result =
+                    self.reverse_pairing_token_check(state, current_char, line.positions[index]);
+                if temp_result != CodeTokenValidateResult::Passed {
+                    #[cfg(feature = "enable_debug_code_token_pairing")]
+                    println!("\ntoken_stack:\n {:#?}", &state.token_stack);
+
+                    return temp_result;
+                }
             }
+
+            index += 1;
         }
 
-        if failed {
-            CodeTokenValidateResult::Fail(
-                line_num,
-                col_num,
-                format!(
-                    "Missing the pairing token '{}' of '{}' at {}:{}",
-                    missing_checking_token, current_checking_token, line_num, col_num
-                ),
-            )
-        } else {
-            CodeTokenValidateResult::Passed
+        // Single line string literal should be closed before the line ends
+        if let ScanMode::InString {
+            delimiter,
+            multiline: false,
+            position,
+        } = &state.mode
+        {
+            return Self::unterminated_result(
+                DiagnosticKind::UnterminatedString,
+                delimiter.clone(),
+                delimiter.clone(),
+                *position,
+            );
         }
+
+        CodeTokenValidateResult::Passed
     }
 
     /// Whether all tokens in the source code are paired. Get the back the detail error when
-    /// failed. The
-    pub fn token_pairing_check<'ptc>(&mut self, source_code: &'ptc str) -> CodeTokenValidateResult {
-        let source_code_to_check = source_code.trim();
-        if source_code_to_check.is_empty() {
-            return CodeTokenValidateResult::Passed;
-        }
+    /// failed.
+    ///
+    /// Which tokens to pair, and which string literals and comments to skip, are decided by
+    /// the `LanguageProfile`.
+    ///
+    /// Each check starts from a clean state, so the same validator can be used again after a
+    /// failed check.
+    pub fn token_pairing_check(&self, source_code: &str) -> CodeTokenValidateResult {
+        self.check_reader(source_code.as_bytes())
+            .expect("Reading from a `&str` never fails")
+    }
 
-        println!("source_code_to_check:\n {}", source_code_to_check);
+    /// Open and check the file line by line, the file won't be loaded into memory at once.
+    pub fn check_file<P: AsRef<Path>>(&self, path: P) -> io::Result<CodeTokenValidateResult> {
+        let file = File::open(path)?;
+        self.check_reader(BufReader::new(file))
+    }
 
-        // 1. Split into lines, then we can report line number when failed.
-        let lines = source_code_to_check.split("\n").into_iter().enumerate();
+    /// Check the source code line by line, stop at the first failure. Only `Err` if reading
+    /// failed or the source isn't valid UTF-8.
+    pub fn check_reader<R: BufRead>(&self, reader: R) -> io::Result<CodeTokenValidateResult> {
+        let mut state = ScanState::new();
+        let temp_result = self.scan_reader(reader, &mut state)?;
+        if temp_result != CodeTokenValidateResult::Passed {
+            return Ok(temp_result);
+        }
 
-        // 2. Walk through lines and columns. Plz keep that in mind, both `line_number` and
-        //    `column_number` are start from `0`!!!
-        for (line_number, line) in lines {
-            for (column_number, temp_char) in line.chars().enumerate() {
-                let token_type = Self::get_token_by_char(temp_char);
-                let need_to_pairing_result = Self::need_to_pairing(token_type);
+        Ok(self.finish_check(state))
+    }
 
-                // Continue next around if no need to do pairing
-                if need_to_pairing_result == NeedToPairingResult::No {
-                    continue;
-                }
+    /// Scan all lines into `state`, stop at the failed line.
+    fn scan_reader<R: BufRead>(
+        &self,
+        mut reader: R,
+        state: &mut ScanState,
+    ) -> io::Result<CodeTokenValidateResult> {
+        let mut line_buffer = String::new();
+        let mut line_number = 0;
+        let mut byte_offset = 0;
 
-                // 3. Let's do a check
-                if let NeedToPairingResult::Yes(st) = need_to_pairing_result {
-                    match st {
-                        TokenType::Braces(c)
-                        | TokenType::Brackets(c)
-                        | TokenType::Parentheses(c) => {
-                            self.token_stack.push(PairToken {
-                                token: c,
-                                line_number: line_number + 1,
-                                column_number: column_number + 1,
-                            });
-                        }
+        // 1. Read line by line, keep tracking the line number and byte offset of each line.
+        loop {
+            line_buffer.clear();
+            let read_len = reader.read_line(&mut line_buffer)?;
+            if read_len == 0 {
+                break;
+            }
 
-                        TokenType::ReverseBraces(_)
-                        | TokenType::ReverseBrackets(_)
-                        | TokenType::ReverseParentheses(_) => {
-                            let temp_result = self.reverse_pairing_token_check(
-                                st,
-                                line_number + 1,
-                                column_number + 1,
-                            );
-                            if temp_result != CodeTokenValidateResult::Passed {
-                                #[cfg(feature = "enable_debug_code_token_pairing")]
-                                println!("\ntoken_stack:\n {:#?}", &self.token_stack);
-
-                                return temp_result;
-                            }
-                        }
+            line_number += 1;
+            let line = line_buffer.strip_suffix('\n').unwrap_or(&line_buffer);
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            let source_line = SourceLine::new(line, line_number, byte_offset, self.column_unit);
+            byte_offset += read_len;
 
-                        TokenType::DoubleQuotes(_) => {
-                            println!(">>>>> 4");
-                        }
-                        _ => {}
-                    };
-                }
+            // 2. Let's do a check
+            let temp_result = self.scan_line(&source_line, state);
+            if temp_result != CodeTokenValidateResult::Passed {
+                return Ok(temp_result);
             }
         }
 
+        Ok(CodeTokenValidateResult::Passed)
+    }
+
+    /// All lines are checked, whatever left in `state` is a failure.
+    fn finish_check(&self, mut state: ScanState) -> CodeTokenValidateResult {
         #[cfg(feature = "enable_debug_code_token_pairing")]
-        println!("token_stack:\n {:#?}", &self.token_stack);
-
-        // 4. If `token_stack` is not empty, that means we got missing pairing there.
-        if !self.token_stack.is_empty() {
-            let pop_token = self.token_stack.pop().unwrap();
-            let missing_checking_token: char;
-
-            if pop_token.token == '(' {
-                missing_checking_token = ')';
-            } else if pop_token.token == '[' {
-                missing_checking_token = ']';
-            } else if pop_token.token == '{' {
-                missing_checking_token = '}';
-            } else if pop_token.token == '"' {
-                missing_checking_token = '"';
-            } else {
-                missing_checking_token = ' ';
+        println!("token_stack:\n {:#?}", &state.token_stack);
+
+        // 3. Unfinished string literal or block comment
+        match state.mode {
+            ScanMode::Code => {}
+            ScanMode::InString {
+                delimiter,
+                position,
+                ..
+            } => {
+                return Self::unterminated_result(
+                    DiagnosticKind::UnterminatedString,
+                    delimiter.clone(),
+                    delimiter,
+                    position,
+                );
+            }
+            ScanMode::InRawString { hashes, position } => {
+                let hashes = "#".repeat(hashes);
+                return Self::unterminated_result(
+                    DiagnosticKind::UnterminatedString,
+                    format!("r{}\"", hashes),
+                    format!("\"{}", hashes),
+                    position,
+                );
             }
+            ScanMode::InBlockComment {
+                opening,
+                closing,
+                position,
+                ..
+            } => {
+                return Self::unterminated_result(
+                    DiagnosticKind::UnterminatedComment,
+                    opening,
+                    closing,
+                    position,
+                );
+            }
+        }
 
-            CodeTokenValidateResult::Fail(
-                pop_token.line_number,
-                pop_token.column_number,
-                format!(
-                    "Missing the pairing token '{}' of '{}' at {}:{}",
-                    missing_checking_token,
-                    pop_token.token,
-                    pop_token.line_number,
-                    pop_token.column_number
-                ),
-            )
-        } else {
-            CodeTokenValidateResult::Passed
+        // 4. If `token_stack` is not empty, that means we got missing pairing there. The
+        //    guessed generic `<` doesn't count.
+        while let Some(pop_token) = state.token_stack.pop() {
+            if self.profile.generic_angle_brackets && pop_token.token == '<' {
+                continue;
+            }
+
+            let missing_checking_token = self.profile.closing_token_of(pop_token.token).unwrap();
+            return CodeTokenValidateResult::Fail(Diagnostic {
+                kind: DiagnosticKind::Unclosed,
+                severity: Severity::Error,
+                token: pop_token.token.to_string(),
+                pairing_token: missing_checking_token.to_string(),
+                primary_span: Self::span_of(pop_token.position, &pop_token.token.to_string()),
+                related_spans: vec![],
+                expected: Some(missing_checking_token.to_string()),
+                found: None,
+            });
         }
+
+        CodeTokenValidateResult::Passed
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::language_profile::StringDelimiter;
     use super::*;
 
+    fn assert_failed_at(
+        result: CodeTokenValidateResult,
+        expected_position: TokenPosition,
+        expected_message: &str,
+    ) {
+        match result {
+            CodeTokenValidateResult::Fail(diagnostic) => {
+                assert_eq!(diagnostic.position(), expected_position);
+                assert_eq!(diagnostic.to_string(), expected_message);
+            }
+            CodeTokenValidateResult::Passed => {
+                panic!("Should fail with: {}", expected_message);
+            }
+        }
+    }
+
+    fn position(line_number: usize, column_number: usize, byte_offset: usize) -> TokenPosition {
+        TokenPosition {
+            line_number,
+            column_number,
+            byte_offset,
+        }
+    }
+
     // #[test]
     // fn pure_token_pairing_should_pass() {
-    // let mut validator = CodeTokenPairingValidator::new();
+    // let validator = CodeTokenPairingValidator::new();
     // let source_code_sample = "{}()[]";
     // let result = validator.passed_token_pairing_check(source_code_sample);
     // println!("result {:#?}", result);
@@ -283,7 +749,7 @@ mod tests {
 
     #[test]
     fn pure_token_pairing_should_fail() {
-        let mut validator = CodeTokenPairingValidator::new();
+        let validator = CodeTokenPairingValidator::new();
         let source_code_sample = "
         {}
         ()
@@ -294,7 +760,7 @@ mod tests {
 
     #[test]
     fn token_pairing_should_pass() {
-        let mut validator = CodeTokenPairingValidator::new();
+        let validator = CodeTokenPairingValidator::new();
         let source_code_sample = "
      pub fn bytes_to_u32(v: &Vec<u8>) -> u32 {
      ((v[3] as u32) << 0x3 * 8)
@@ -310,7 +776,7 @@ mod tests {
 
     #[test]
     fn token_pairing_should_fail() {
-        let mut validator = CodeTokenPairingValidator::new();
+        let validator = CodeTokenPairingValidator::new();
         let source_code_sample = "
      pub fn bytes_to_u32(v: &Vec<u8>) -> u32
      ((v[1] as u32) << 0x1 * 8)
@@ -320,4 +786,366 @@ mod tests {
         let result = validator.token_pairing_check(source_code_sample);
         println!("result {:#?}", result);
     }
+
+    #[test]
+    fn rust_profile_should_skip_strings_chars_and_comments() {
+        let validator = CodeTokenPairingValidator::with_profile(LanguageProfile::rust());
+        let source_code_sample = r###"
+     // Unpaired in comment: (
+     /* { /* nested [ */ still comment ( */
+     fn token<'a>(s: &'a str) -> Vec<Vec<u8>> {
+         let open = '{';
+         let escaped = '\'';
+         let text = "([{ \" ";
+         let raw = r#"no "pairing" ) here"#;
+         let bytes = br"]";
+         Vec::<Vec<u8>>::new()
+     }
+     "###;
+
+        let result = validator.token_pairing_check(source_code_sample);
+        assert_eq!(result, CodeTokenValidateResult::Passed);
+    }
+
+    #[test]
+    fn rust_profile_should_treat_angle_brackets_by_context() {
+        let validator = CodeTokenPairingValidator::with_profile(LanguageProfile::rust());
+        let source_code_sample = "
+     pub fn bytes_to_u32(v: &Vec<u8>) -> u32 {
+     if v[0] < 1 && v[1] > 2 { return 0; }
+     ((v[3] as u32) << 0x3 * 8) | ((v[2] as u32) >> 0x2 * 8)
+     }
+     ";
+        let result = validator.token_pairing_check(source_code_sample);
+        assert_eq!(result, CodeTokenValidateResult::Passed);
+
+        let validator = CodeTokenPairingValidator::with_profile(LanguageProfile::rust());
+        let result = validator.token_pairing_check("let x: Option<(u8]> = None;");
+        assert_failed_at(
+            result,
+            position(1, 18, 17),
+            "Missing the pairing token '[' of ']' at 1:18",
+        );
+    }
+
+    #[test]
+    fn unclosed_string_and_comment_should_fail() {
+        let validator = CodeTokenPairingValidator::with_profile(LanguageProfile::rust());
+        let result = validator.token_pairing_check("let s = \"abc;\nfn a() {}");
+        assert_failed_at(
+            result,
+            position(1, 9, 8),
+            "Missing the pairing token '\"' of '\"' at 1:9",
+        );
+
+        let validator = CodeTokenPairingValidator::with_profile(LanguageProfile::rust());
+        let result = validator.token_pairing_check("fn a() {}\n/* /* */");
+        assert_failed_at(
+            result,
+            position(2, 1, 10),
+            "Missing the pairing token '*/' of '/*' at 2:1",
+        );
+
+        let validator = CodeTokenPairingValidator::with_profile(LanguageProfile::rust());
+        let result = validator.token_pairing_check("let s = r##\"abc\"#;");
+        assert_failed_at(
+            result,
+            position(1, 9, 8),
+            "Missing the pairing token '\"##' of 'r##\"' at 1:9",
+        );
+    }
+
+    #[test]
+    fn c_profile_should_not_allow_multiline_strings() {
+        let validator = CodeTokenPairingValidator::with_profile(LanguageProfile::c());
+        let result = validator.token_pairing_check("char c = '{'; /* } */ int a[2] = {0, 1};");
+        assert_eq!(result, CodeTokenValidateResult::Passed);
+
+        let validator = CodeTokenPairingValidator::with_profile(LanguageProfile::c());
+        let result = validator.token_pairing_check("puts(\"abc);\n\");");
+        assert_failed_at(
+            result,
+            position(1, 6, 5),
+            "Missing the pairing token '\"' of '\"' at 1:6",
+        );
+    }
+
+    #[test]
+    fn json_profile_should_work() {
+        let validator = CodeTokenPairingValidator::with_profile(LanguageProfile::json());
+        let result = validator.token_pairing_check(r#"{"a": "}", "b": [1, "(", {"c": null}]}"#);
+        assert_eq!(result, CodeTokenValidateResult::Passed);
+
+        let validator = CodeTokenPairingValidator::with_profile(LanguageProfile::json());
+        let result = validator.token_pairing_check(r#"{"a": [1, 2}"#);
+        assert_failed_at(
+            result,
+            position(1, 12, 11),
+            "Missing the pairing token '{' of '}' at 1:12",
+        );
+    }
+
+    #[test]
+    fn python_profile_should_work() {
+        let validator = CodeTokenPairingValidator::with_profile(LanguageProfile::python());
+        let source_code_sample = r#"
+     def f(a):  # (
+         """
+         Docstring with ( and ' inside
+         """
+         return [a, ")", '(']
+     "#;
+        let result = validator.token_pairing_check(source_code_sample);
+        assert_eq!(result, CodeTokenValidateResult::Passed);
+    }
+
+    #[test]
+    fn user_defined_profile_should_work() {
+        let html_template_profile = LanguageProfile {
+            name: "html-template".to_string(),
+            pairs: vec![('{', '}'), ('(', ')'), ('<', '>')],
+            string_delimiters: vec![StringDelimiter::new("\"", true)],
+            block_comments: vec![("<!--".to_string(), "-->".to_string())],
+            ..LanguageProfile::default()
+        };
+
+        let validator = CodeTokenPairingValidator::with_profile(html_template_profile.clone());
+        let result =
+            validator.token_pairing_check("<!-- < --><div class=\">\">{{ item(1) }}</div>");
+        assert_eq!(result, CodeTokenValidateResult::Passed);
+
+        let validator = CodeTokenPairingValidator::with_profile(html_template_profile);
+        let result = validator.token_pairing_check("<div {{ x }}");
+        assert_failed_at(
+            result,
+            position(1, 1, 0),
+            "Missing the pairing token '>' of '<' at 1:1",
+        );
+    }
+
+    #[test]
+    fn get_token_by_char_should_classify_all_brackets() {
+        let cases = vec![
+            ('{', TokenType::Braces('{')),
+            ('}', TokenType::ReverseBraces('}')),
+            ('[', TokenType::Brackets('[')),
+            (']', TokenType::ReverseBrackets(']')),
+            ('(', TokenType::Parentheses('(')),
+            (')', TokenType::ReverseParentheses(')')),
+            ('<', TokenType::Arrows('<')),
+            ('>', TokenType::ReverseArrows('>')),
+        ];
+
+        for (c, expected_token_type) in cases {
+            assert_eq!(
+                CodeTokenPairingValidator::get_token_by_char(c),
+                expected_token_type
+            );
+        }
+    }
+
+    #[test]
+    fn every_opener_and_closer_combination_should_be_checked() {
+        let validator = CodeTokenPairingValidator::new();
+        let pairs = [('{', '}'), ('[', ']'), ('(', ')')];
+
+        for (opening, _) in pairs.iter() {
+            for (expected_opening, closing) in pairs.iter() {
+                let source_code_sample = format!("{}{}", opening, closing);
+                let result = validator.token_pairing_check(&source_code_sample);
+
+                if opening == expected_opening {
+                    assert_eq!(result, CodeTokenValidateResult::Passed);
+                } else {
+                    assert_failed_at(
+                        result,
+                        position(1, 2, 1),
+                        &format!(
+                            "Missing the pairing token '{}' of '{}' at 1:2",
+                            expected_opening, closing
+                        ),
+                    );
+                }
+            }
+
+            // Opener only
+            let source_code_sample = format!("a{}", opening);
+            let closing = validator.profile().closing_token_of(*opening).unwrap();
+            assert_failed_at(
+                validator.token_pairing_check(&source_code_sample),
+                position(1, 2, 1),
+                &format!(
+                    "Missing the pairing token '{}' of '{}' at 1:2",
+                    closing, opening
+                ),
+            );
+        }
+
+        // Closer only
+        for (opening, closing) in pairs.iter() {
+            assert_failed_at(
+                validator.token_pairing_check(&closing.to_string()),
+                position(1, 1, 0),
+                &format!(
+                    "Missing the pairing token '{}' of '{}' at 1:1",
+                    opening, closing
+                ),
+            );
+        }
+    }
+
+    #[test]
+    fn validator_should_be_reusable_after_failure() {
+        let validator = CodeTokenPairingValidator::new();
+
+        assert_failed_at(
+            validator.token_pairing_check("{ ( [ ]"),
+            position(1, 3, 2),
+            "Missing the pairing token ')' of '(' at 1:3",
+        );
+        assert_failed_at(
+            validator.token_pairing_check("{ ( ] }"),
+            position(1, 5, 4),
+            "Missing the pairing token '[' of ']' at 1:5",
+        );
+        assert_eq!(
+            validator.token_pairing_check("fn a() { let v = [1, 2]; }"),
+            CodeTokenValidateResult::Passed
+        );
+    }
+
+    #[test]
+    fn positions_should_point_to_the_untrimmed_source() {
+        let validator = CodeTokenPairingValidator::new();
+
+        assert_failed_at(
+            validator.token_pairing_check("\n\n   {\n"),
+            position(3, 4, 5),
+            "Missing the pairing token '}' of '{' at 3:4",
+        );
+
+        // `\r\n` line endings are counted in `byte_offset`, but not in `column_number`
+        assert_failed_at(
+            validator.token_pairing_check("a\r\n)\r\n"),
+            position(2, 1, 3),
+            "Missing the pairing token '(' of ')' at 2:1",
+        );
+    }
+
+    #[test]
+    fn column_should_be_counted_by_column_unit() {
+        // `e` + combining acute accent, 2 chars, 3 bytes, 1 grapheme
+        let source_code_sample = "e\u{301}(";
+
+        let validator = CodeTokenPairingValidator::new();
+        assert_failed_at(
+            validator.token_pairing_check(source_code_sample),
+            position(1, 3, 3),
+            "Missing the pairing token ')' of '(' at 1:3",
+        );
+
+        let validator = CodeTokenPairingValidator::new().with_column_unit(ColumnUnit::Grapheme);
+        assert_failed_at(
+            validator.token_pairing_check(source_code_sample),
+            position(1, 2, 3),
+            "Missing the pairing token ')' of '(' at 1:2",
+        );
+    }
+
+    #[test]
+    fn check_reader_and_check_file_should_work() {
+        let validator = CodeTokenPairingValidator::with_profile(LanguageProfile::rust());
+
+        let result = validator.check_reader("fn a() {\n    [1, 2]\n}\n".as_bytes());
+        assert_eq!(result.unwrap(), CodeTokenValidateResult::Passed);
+
+        // Invalid UTF-8
+        let result = validator.check_reader(&[b'(', 0xff, b')'][..]);
+        assert_eq!(result.unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+
+        let temp_file = std::env::temp_dir().join("code_token_pairing_validator_check_file.rs");
+        std::fs::write(&temp_file, "fn a() {\n    let s = \"}\";\n    (1, 2]\n}\n").unwrap();
+        let result = validator.check_file(&temp_file);
+        std::fs::remove_file(&temp_file).unwrap();
+        assert_failed_at(
+            result.unwrap(),
+            position(3, 10, 35),
+            "Missing the pairing token '[' of ']' at 3:10",
+        );
+
+        let result = validator.check_file("not_exists_source_file.rs");
+        assert_eq!(result.unwrap_err().kind(), std::io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn diagnostic_should_describe_each_failure_kind() {
+        let validator = CodeTokenPairingValidator::with_profile(LanguageProfile::rust());
+        let diagnostic_of = |source_code: &str| match validator.token_pairing_check(source_code) {
+            CodeTokenValidateResult::Fail(diagnostic) => diagnostic,
+            CodeTokenValidateResult::Passed => panic!("'{}' should fail", source_code),
+        };
+
+        let diagnostic = diagnostic_of("fn a() {\n    (1]\n}");
+        assert_eq!(diagnostic.kind, DiagnosticKind::Mismatch);
+        assert_eq!(diagnostic.severity, Severity::Error);
+        assert_eq!(diagnostic.token, "]");
+        assert_eq!(diagnostic.pairing_token, "[");
+        assert_eq!(diagnostic.expected, Some(")".to_string()));
+        assert_eq!(diagnostic.found, Some("]".to_string()));
+        assert_eq!(
+            diagnostic.primary_span,
+            Span {
+                start: position(2, 7, 15),
+                byte_len: 1
+            }
+        );
+        assert_eq!(
+            diagnostic.related_spans,
+            vec![Span {
+                start: position(2, 5, 13),
+                byte_len: 1
+            }]
+        );
+
+        let diagnostic = diagnostic_of("a)");
+        assert_eq!(diagnostic.kind, DiagnosticKind::UnexpectedCloser);
+        assert_eq!(diagnostic.expected, None);
+        assert_eq!(diagnostic.found, Some(")".to_string()));
+
+        let diagnostic = diagnostic_of("vec![1");
+        assert_eq!(diagnostic.kind, DiagnosticKind::Unclosed);
+        assert_eq!(diagnostic.expected, Some("]".to_string()));
+        assert_eq!(diagnostic.found, None);
+
+        let diagnostic = diagnostic_of("let s = r#\"abc");
+        assert_eq!(diagnostic.kind, DiagnosticKind::UnterminatedString);
+        assert_eq!(diagnostic.primary_span.byte_len, 3);
+        assert_eq!(diagnostic.expected, Some("\"#".to_string()));
+
+        let diagnostic = diagnostic_of("/* abc");
+        assert_eq!(diagnostic.kind, DiagnosticKind::UnterminatedComment);
+        assert_eq!(
+            diagnostic.to_string(),
+            "Missing the pairing token '*/' of '/*' at 1:1"
+        );
+    }
+
+    #[test]
+    fn diagnostic_should_be_serializable() {
+        let validator = CodeTokenPairingValidator::new();
+        let diagnostic = match validator.token_pairing_check("(]") {
+            CodeTokenValidateResult::Fail(diagnostic) => diagnostic,
+            CodeTokenValidateResult::Passed => panic!("'(]' should fail"),
+        };
+
+        let json = serde_json::to_value(&diagnostic).unwrap();
+        assert_eq!(json["kind"], "Mismatch");
+        assert_eq!(json["severity"], "Error");
+        assert_eq!(json["primary_span"]["start"]["line_number"], 1);
+        assert_eq!(json["primary_span"]["start"]["column_number"], 2);
+        assert_eq!(json["primary_span"]["start"]["byte_offset"], 1);
+        assert_eq!(json["related_spans"][0]["start"]["column_number"], 1);
+        assert_eq!(json["expected"], ")");
+        assert_eq!(json["found"], "]");
+    }
 }
@@ -0,0 +1,509 @@
+use super::diagnostic::{Diagnostic, DiagnosticKind, Span};
+use super::{CodeTokenPairingValidator, CodeTokenValidateResult, ColumnUnit, TokenPosition};
+use serde::Serialize;
+use unicode_segmentation::UnicodeSegmentation;
+
+///
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize)]
+pub enum FixKind {
+    // Insert the missing closing token (or string/comment delimiter)
+    InsertClosing,
+    // Delete a closing token which doesn't have any opening token
+    DeleteClosing,
+    // Swap a mismatched closing token with the expected one
+    ReplaceClosing,
+}
+
+///
+/// # `SuggestedEdit`
+///
+/// A machine-applicable fix: replace the `span` in the original source with `replacement`.
+/// An insertion has an empty span (`byte_len` is `0`).
+///
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
+pub struct SuggestedEdit {
+    pub kind: FixKind,
+    pub span: Span,
+    pub replacement: String,
+}
+
+/// A fix found in the current (already fixed) source, it's converted to the `SuggestedEdit`
+/// against the original source later.
+#[derive(Debug)]
+struct PendingEdit {
+    kind: FixKind,
+    byte_offset: usize,
+    byte_len: usize,
+    replacement: String,
+}
+
+/// Apply all edits to the source and get back the fixed source.
+///
+/// Edits are applied by the `span` order, edits at the same place are applied by the given
+/// order. An edit overlaps with the previous one will be ignored.
+pub fn apply_edits(source_code: &str, edits: &[SuggestedEdit]) -> String {
+    let mut sorted_edits: Vec<&SuggestedEdit> = edits.iter().collect();
+    sorted_edits.sort_by_key(|edit| edit.span.start.byte_offset);
+
+    let mut fixed_source = String::with_capacity(source_code.len());
+    let mut cursor = 0;
+    for edit in sorted_edits {
+        let start = edit.span.start.byte_offset;
+        if start < cursor || start + edit.span.byte_len > source_code.len() {
+            continue;
+        }
+
+        fixed_source.push_str(&source_code[cursor..start]);
+        fixed_source.push_str(&edit.replacement);
+        cursor = start + edit.span.byte_len;
+    }
+    fixed_source.push_str(&source_code[cursor..]);
+
+    fixed_source
+}
+
+/// `(start, end)` byte range of each line, `end` excludes the line break.
+fn line_ranges(source_code: &str) -> Vec<(usize, usize)> {
+    let mut ranges = Vec::<(usize, usize)>::new();
+    let mut start = 0;
+    for line in source_code.split('\n') {
+        ranges.push((start, start + line.trim_end_matches('\r').len()));
+        start += line.len() + 1;
+    }
+    ranges
+}
+
+///
+fn line_index_of(ranges: &[(usize, usize)], byte_offset: usize) -> usize {
+    ranges
+        .iter()
+        .rposition(|(start, _)| *start <= byte_offset)
+        .unwrap_or(0)
+}
+
+/// The leading whitespace of a line, `None` for a blank line
+fn indentation_of(line: &str) -> Option<&str> {
+    let content = line.trim_start();
+    if content.is_empty() {
+        None
+    } else {
+        Some(&line[..line.len() - content.len()])
+    }
+}
+
+///
+impl CodeTokenPairingValidator {
+    /// Suggest the edits to make all tokens paired, `apply_edits` can apply them to get the
+    /// fixed source.
+    ///
+    /// - A missing closing token is inserted by the indentation: after a deeper indented block
+    ///   it goes into a new line with the opening line's indentation, otherwise it goes to the
+    ///   end of the opening line (before a trailing `;` or `,`).
+    /// - A stray closing token is deleted, the whole line is deleted if nothing else is there.
+    /// - A mismatched closing token is swapped with the expected one, unless it looks like the
+    ///   end of an outer block (the first token of a less indented line), then the missing
+    ///   closing token is inserted instead.
+    pub fn suggest_fixes(&self, source_code: &str) -> Vec<SuggestedEdit> {
+        let mut edits = Vec::<SuggestedEdit>::new();
+        let mut fixed_source = source_code.to_string();
+
+        // Each fix resolves at least one token, so it never needs more rounds than that.
+        for _ in 0..=source_code.len() {
+            let diagnostic = match self.token_pairing_check(&fixed_source) {
+                CodeTokenValidateResult::Passed => break,
+                CodeTokenValidateResult::Fail(diagnostic) => diagnostic,
+            };
+
+            let pending_edit = match self.fix_for(&fixed_source, &diagnostic) {
+                Some(pending_edit) => pending_edit,
+                None => break,
+            };
+
+            let original_offset = match Self::original_offset_of(&edits, pending_edit.byte_offset) {
+                Some(original_offset) => original_offset,
+                None => break,
+            };
+
+            edits.push(SuggestedEdit {
+                kind: pending_edit.kind,
+                span: Span {
+                    start: self.position_at(source_code, original_offset),
+                    byte_len: pending_edit.byte_len,
+                },
+                replacement: pending_edit.replacement,
+            });
+            fixed_source = apply_edits(source_code, &edits);
+        }
+
+        edits
+    }
+
+    /// Convert a byte offset in the fixed source back to the original source, `None` if it's
+    /// inside the inserted text.
+    fn original_offset_of(edits: &[SuggestedEdit], fixed_offset: usize) -> Option<usize> {
+        let mut sorted_edits: Vec<&SuggestedEdit> = edits.iter().collect();
+        sorted_edits.sort_by_key(|edit| edit.span.start.byte_offset);
+
+        // `fixed_offset - original_offset` so far
+        let mut delta: isize = 0;
+        for edit in sorted_edits {
+            let fixed_start = (edit.span.start.byte_offset as isize + delta) as usize;
+            if fixed_offset < fixed_start {
+                break;
+            }
+
+            let replacement_end = fixed_start + edit.replacement.len();
+            if fixed_offset < replacement_end {
+                return None;
+            }
+
+            delta += edit.replacement.len() as isize - edit.span.byte_len as isize;
+        }
+
+        Some((fixed_offset as isize - delta) as usize)
+    }
+
+    ///
+    fn position_at(&self, source_code: &str, byte_offset: usize) -> TokenPosition {
+        let line_start = source_code[..byte_offset]
+            .rfind('\n')
+            .map(|pos| pos + 1)
+            .unwrap_or(0);
+        let before_in_line = &source_code[line_start..byte_offset];
+        let column_count = match self.column_unit {
+            ColumnUnit::UnicodeScalar => before_in_line.chars().count(),
+            ColumnUnit::Grapheme => before_in_line.graphemes(true).count(),
+        };
+
+        TokenPosition {
+            line_number: source_code[..line_start].matches('\n').count() + 1,
+            column_number: column_count + 1,
+            byte_offset,
+        }
+    }
+
+    ///
+    fn fix_for(&self, source_code: &str, diagnostic: &Diagnostic) -> Option<PendingEdit> {
+        let ranges = line_ranges(source_code);
+        let primary_offset = diagnostic.primary_span.start.byte_offset;
+
+        match diagnostic.kind {
+            DiagnosticKind::UnexpectedCloser => {
+                let line_index = line_index_of(&ranges, primary_offset);
+                let (line_start, line_end) = ranges[line_index];
+                let line = &source_code[line_start..line_end];
+
+                // Nothing else in this line, delete the whole line (including the line break)
+                match ranges.get(line_index + 1) {
+                    Some((next_line_start, _)) if line.trim() == diagnostic.token => {
+                        Some(PendingEdit {
+                            kind: FixKind::DeleteClosing,
+                            byte_offset: line_start,
+                            byte_len: next_line_start - line_start,
+                            replacement: String::new(),
+                        })
+                    }
+                    _ => Some(PendingEdit {
+                        kind: FixKind::DeleteClosing,
+                        byte_offset: primary_offset,
+                        byte_len: diagnostic.primary_span.byte_len,
+                        replacement: String::new(),
+                    }),
+                }
+            }
+            DiagnosticKind::Mismatch => {
+                let opening_span = diagnostic.related_spans.first()?;
+                let expected = diagnostic.expected.clone()?;
+                let opening_line_index = line_index_of(&ranges, opening_span.start.byte_offset);
+                let closing_line_index = line_index_of(&ranges, primary_offset);
+
+                let (opening_line_start, opening_line_end) = ranges[opening_line_index];
+                let (closing_line_start, closing_line_end) = ranges[closing_line_index];
+                let opening_indentation =
+                    indentation_of(&source_code[opening_line_start..opening_line_end])?.len();
+                let closing_line = &source_code[closing_line_start..closing_line_end];
+                let closing_indentation = indentation_of(closing_line)?.len();
+
+                let ends_outer_block = closing_line_index > opening_line_index
+                    && closing_line_start + closing_indentation == primary_offset
+                    && closing_indentation <= opening_indentation;
+
+                if ends_outer_block {
+                    let (byte_offset, replacement) = Self::insertion_for(
+                        source_code,
+                        &ranges,
+                        opening_span.start.byte_offset,
+                        &expected,
+                    );
+                    Some(PendingEdit {
+                        kind: FixKind::InsertClosing,
+                        byte_offset,
+                        byte_len: 0,
+                        replacement,
+                    })
+                } else {
+                    Some(PendingEdit {
+                        kind: FixKind::ReplaceClosing,
+                        byte_offset: primary_offset,
+                        byte_len: diagnostic.primary_span.byte_len,
+                        replacement: expected,
+                    })
+                }
+            }
+            DiagnosticKind::Unclosed => {
+                let (byte_offset, replacement) = Self::insertion_for(
+                    source_code,
+                    &ranges,
+                    primary_offset,
+                    &diagnostic.pairing_token,
+                );
+                Some(PendingEdit {
+                    kind: FixKind::InsertClosing,
+                    byte_offset,
+                    byte_len: 0,
+                    replacement,
+                })
+            }
+            // A missing quote is most likely in the same line
+            DiagnosticKind::UnterminatedString => {
+                let (_, line_end) = ranges[line_index_of(&ranges, primary_offset)];
+                Some(PendingEdit {
+                    kind: FixKind::InsertClosing,
+                    byte_offset: Self::before_trailing_punctuation(source_code, line_end),
+                    byte_len: 0,
+                    replacement: diagnostic.pairing_token.clone(),
+                })
+            }
+            DiagnosticKind::UnterminatedComment => Some(PendingEdit {
+                kind: FixKind::InsertClosing,
+                byte_offset: source_code.trim_end().len(),
+                byte_len: 0,
+                replacement: format!(" {}", diagnostic.pairing_token),
+            }),
+        }
+    }
+
+    /// The end of the line content, but before the trailing `;` or `,`
+    fn before_trailing_punctuation(source_code: &str, line_end: usize) -> usize {
+        let content_end = source_code[..line_end].trim_end().len();
+        if source_code[..content_end].ends_with(';') || source_code[..content_end].ends_with(',') {
+            content_end - 1
+        } else {
+            content_end
+        }
+    }
+
+    /// Where and what to insert for the missing `closing_token` of the opening token at
+    /// `opening_offset`, decided by the indentation of the following lines.
+    fn insertion_for(
+        source_code: &str,
+        ranges: &[(usize, usize)],
+        opening_offset: usize,
+        closing_token: &str,
+    ) -> (usize, String) {
+        let opening_line_index = line_index_of(ranges, opening_offset);
+        let (opening_line_start, opening_line_end) = ranges[opening_line_index];
+        let opening_indentation =
+            indentation_of(&source_code[opening_line_start..opening_line_end]).unwrap_or("");
+
+        let following_indentations: Vec<(usize, usize)> = ranges[opening_line_index + 1..]
+            .iter()
+            .filter_map(|(start, end)| {
+                indentation_of(&source_code[*start..*end])
+                    .map(|indentation| (*start, indentation.len()))
+            })
+            .collect();
+
+        // The next line is not deeper indented, close it in the same line
+        match following_indentations.first() {
+            Some((_, indentation)) if *indentation > opening_indentation.len() => {}
+            _ => {
+                return (
+                    Self::before_trailing_punctuation(source_code, opening_line_end),
+                    closing_token.to_string(),
+                )
+            }
+        }
+
+        // Close the block right before the first line which is back to the same indentation
+        match following_indentations
+            .iter()
+            .find(|(_, indentation)| *indentation <= opening_indentation.len())
+        {
+            Some((line_start, _)) => (
+                *line_start,
+                format!("{}{}\n", opening_indentation, closing_token),
+            ),
+            None if source_code.ends_with('\n') => (
+                source_code.len(),
+                format!("{}{}\n", opening_indentation, closing_token),
+            ),
+            None => (
+                source_code.len(),
+                format!("\n{}{}", opening_indentation, closing_token),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::language_profile::LanguageProfile;
+    use super::*;
+
+    fn fix(source_code: &str) -> (Vec<SuggestedEdit>, String) {
+        let validator = CodeTokenPairingValidator::with_profile(LanguageProfile::rust());
+        let edits = validator.suggest_fixes(source_code);
+        let fixed_source = apply_edits(source_code, &edits);
+
+        assert_eq!(
+            validator.token_pairing_check(&fixed_source),
+            CodeTokenValidateResult::Passed
+        );
+        (edits, fixed_source)
+    }
+
+    #[test]
+    fn passed_source_should_not_have_any_fix() {
+        let source_code_sample = "fn a() -> Vec<u8> {\n    vec![1, 2]\n}\n";
+        let (edits, fixed_source) = fix(source_code_sample);
+        assert!(edits.is_empty());
+        assert_eq!(fixed_source, source_code_sample);
+    }
+
+    #[test]
+    fn missing_block_closer_should_be_inserted_after_the_block() {
+        let source_code_sample = "\
+pub fn bytes_to_u32(v: &Vec<u8>) -> u32 {
+    ((v[3] as u32) << 0x3 * 8)
+    | ((v[2] as u32) << 0x2 * 8)
+";
+        let (edits, fixed_source) = fix(source_code_sample);
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0].kind, FixKind::InsertClosing);
+        assert_eq!(edits[0].span.start.line_number, 4);
+        assert_eq!(edits[0].span.byte_len, 0);
+        assert_eq!(fixed_source, format!("{}{}", source_code_sample, "}\n"));
+    }
+
+    #[test]
+    fn missing_inline_closer_should_be_inserted_before_semicolon() {
+        let source_code_sample = "\
+fn main() {
+    let v = vec![1, 2;
+    println!(\"{:?}\", v);
+}
+";
+        let (edits, fixed_source) = fix(source_code_sample);
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0].kind, FixKind::InsertClosing);
+        assert_eq!(edits[0].span.start.line_number, 2);
+        assert_eq!(edits[0].span.start.column_number, 22);
+        assert_eq!(edits[0].replacement, "]");
+        assert_eq!(
+            fixed_source,
+            "fn main() {\n    let v = vec![1, 2];\n    println!(\"{:?}\", v);\n}\n"
+        );
+    }
+
+    #[test]
+    fn stray_closer_line_should_be_deleted() {
+        let source_code_sample = "\
+pub fn bytes_to_u32(v: &Vec<u8>) -> u32
+((v[1] as u32) << 0x1 * 8)
+}
+";
+        let (edits, fixed_source) = fix(source_code_sample);
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0].kind, FixKind::DeleteClosing);
+        assert_eq!(edits[0].span.start.line_number, 3);
+        assert_eq!(edits[0].span.byte_len, 2);
+        assert_eq!(
+            fixed_source,
+            "pub fn bytes_to_u32(v: &Vec<u8>) -> u32\n((v[1] as u32) << 0x1 * 8)\n"
+        );
+    }
+
+    #[test]
+    fn mismatched_closer_should_be_swapped() {
+        let source_code_sample = "let x = ((v[1] as u32] << 0x1 * 8);";
+        let (edits, fixed_source) = fix(source_code_sample);
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0].kind, FixKind::ReplaceClosing);
+        assert_eq!(edits[0].span.start.column_number, 22);
+        assert_eq!(edits[0].span.byte_len, 1);
+        assert_eq!(fixed_source, "let x = ((v[1] as u32) << 0x1 * 8);");
+    }
+
+    #[test]
+    fn multiple_problems_should_be_fixed_against_the_original_source() {
+        let source_code_sample = "\
+fn main() {
+    let s = String::new();
+    let t = (1, 2];
+    if s.len() > 0 {
+        println!(\"{}\", s);
+";
+        let (edits, fixed_source) = fix(source_code_sample);
+        let kinds: Vec<FixKind> = edits.iter().map(|edit| edit.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                FixKind::ReplaceClosing,
+                FixKind::InsertClosing,
+                FixKind::InsertClosing,
+            ]
+        );
+        assert_eq!(
+            fixed_source,
+            "\
+fn main() {
+    let s = String::new();
+    let t = (1, 2);
+    if s.len() > 0 {
+        println!(\"{}\", s);
+    }
+}
+"
+        );
+
+        // Every span is against the original source
+        assert_eq!(edits[0].span.start.line_number, 3);
+        assert_eq!(
+            &source_code_sample[edits[0].span.start.byte_offset..][..1],
+            "]"
+        );
+        assert_eq!(edits[1].span.start.line_number, 6);
+        assert_eq!(edits[2].span.start.line_number, 6);
+    }
+
+    #[test]
+    fn unterminated_string_and_comment_should_be_closed() {
+        let (edits, fixed_source) = fix("fn a() {}\nlet s = \"abc;\n");
+        assert_eq!(edits.len(), 1);
+        assert_eq!(fixed_source, "fn a() {}\nlet s = \"abc\";\n");
+
+        let (edits, fixed_source) = fix("fn a() {}\n/* todo\n");
+        assert_eq!(edits.len(), 1);
+        assert_eq!(fixed_source, "fn a() {}\n/* todo */\n");
+    }
+
+    #[test]
+    fn apply_edits_should_ignore_overlapped_edits() {
+        let edit_at = |byte_offset: usize, byte_len: usize, replacement: &str| SuggestedEdit {
+            kind: FixKind::ReplaceClosing,
+            span: Span {
+                start: TokenPosition {
+                    line_number: 1,
+                    column_number: byte_offset + 1,
+                    byte_offset,
+                },
+                byte_len,
+            },
+            replacement: replacement.to_string(),
+        };
+
+        let edits = vec![edit_at(1, 2, "X"), edit_at(2, 1, "Y"), edit_at(0, 0, "<")];
+        assert_eq!(apply_edits("abcd", &edits), "<aXd");
+    }
+}
@@ -0,0 +1,96 @@
+use super::TokenPosition;
+use serde::Serialize;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+///
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize)]
+pub enum DiagnosticKind {
+    // An opening token never gets its closing token, e.g. `(` in `(a`
+    Unclosed,
+    // A closing token without any opening token before it, e.g. `)` in `a)`
+    UnexpectedCloser,
+    // The closing token doesn't match the latest opening token, e.g. `]` in `(a]`
+    Mismatch,
+    // A string literal (including raw string) never ends
+    UnterminatedString,
+    // A block comment never ends
+    UnterminatedComment,
+}
+
+///
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A token range in the source code, `byte_len` is the token length in bytes.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize)]
+pub struct Span {
+    pub start: TokenPosition,
+    pub byte_len: usize,
+}
+
+///
+/// # `Diagnostic`
+///
+/// The detail of a failed pairing check, it can be filtered by `kind` or `severity`, or
+/// formatted in any way by the caller.
+///
+/// `Display` gives the default message, e.g.:
+///
+/// `Missing the pairing token '[' of ']' at 3:10`
+///
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
+pub struct Diagnostic {
+    pub kind: DiagnosticKind,
+    pub severity: Severity,
+
+    // The token at `primary_span`
+    pub token: String,
+
+    // The token should pair with `token`
+    pub pairing_token: String,
+
+    // Where the problem is reported
+    pub primary_span: Span,
+
+    // Other places related to the problem, e.g. the opening token of a `Mismatch`
+    pub related_spans: Vec<Span>,
+
+    // The closing token which is expected to see (if any)
+    pub expected: Option<String>,
+
+    // The closing token which is actually found (if any)
+    pub found: Option<String>,
+}
+
+///
+impl Diagnostic {
+    /// Where the problem is reported, it's the start of `primary_span`.
+    pub fn position(&self) -> TokenPosition {
+        self.primary_span.start
+    }
+
+    /// The default message without the position, e.g. `Missing the pairing token '[' of ']'`
+    pub fn message(&self) -> String {
+        format!(
+            "Missing the pairing token '{}' of '{}'",
+            self.pairing_token, self.token
+        )
+    }
+}
+
+///
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let position = self.position();
+        write!(
+            f,
+            "{} at {}:{}",
+            self.message(),
+            position.line_number,
+            position.column_number
+        )
+    }
+}
@@ -0,0 +1,508 @@
+use super::diagnostic::Span;
+use super::{
+    CodeTokenPairingValidator, CodeTokenValidateResult, ColumnUnit, PairToken, ScanMode, ScanState,
+    SourceLine, TokenPosition,
+};
+use crate::stack::stack::Stack;
+use std::ops::Range;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Replace the `range` (byte range in the current source) with `new_text`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct TextEdit {
+    pub range: Range<usize>,
+    pub new_text: String,
+}
+
+/// How an edit moves the positions after it, only used for comparing the new scan state with
+/// the previous one.
+#[derive(Debug)]
+struct EditShift {
+    // Byte offset in the old source where the replaced range ends
+    old_end: usize,
+    // 0-based line index in the old source where the replaced range ends
+    old_end_line: usize,
+    line_delta: isize,
+    byte_delta: isize,
+}
+
+///
+/// # `IncrementalTokenPairingValidator`
+///
+/// Keeps the source and a checkpoint (the `Stack` and the string/comment state) at the start
+/// of each line. After an edit, it only re-checks from the edited line until the state is the
+/// same as the previous check again, the rest lines reuse the previous checkpoints.
+///
+/// ```
+/// # use data_structure_implementation_by_rust::stack::source_code_token_pairing_validator::{
+/// #     incremental::{IncrementalTokenPairingValidator, TextEdit},
+/// #     language_profile::LanguageProfile,
+/// #     CodeTokenPairingValidator, CodeTokenValidateResult,
+/// # };
+/// let mut validator = IncrementalTokenPairingValidator::new(
+///     CodeTokenPairingValidator::with_profile(LanguageProfile::rust()),
+///     "fn a() {\n}\n",
+/// );
+/// let result = validator.apply_edit(&TextEdit {
+///     range: 8..8,
+///     new_text: "(".to_string(),
+/// });
+/// assert!(matches!(result, CodeTokenValidateResult::Fail(_)));
+/// ```
+///
+#[derive(Debug)]
+pub struct IncrementalTokenPairingValidator {
+    validator: CodeTokenPairingValidator,
+    source_code: String,
+
+    // Byte offset of each line start
+    line_starts: Vec<usize>,
+
+    // `checkpoints[i]` is the state before checking line `i`, the extra last one is the state
+    // after all lines. It stops at the failed line if the check failed in the middle.
+    checkpoints: Vec<ScanState>,
+
+    result: CodeTokenValidateResult,
+
+    // How many lines are checked by the last `apply_edit`, for tuning and testing purpose
+    last_scanned_lines: usize,
+}
+
+///
+impl IncrementalTokenPairingValidator {
+    /// Check the entire source once
+    pub fn new(validator: CodeTokenPairingValidator, source_code: &str) -> Self {
+        let mut incremental_validator = Self {
+            validator,
+            source_code: source_code.to_string(),
+            line_starts: vec![],
+            checkpoints: vec![ScanState::new()],
+            result: CodeTokenValidateResult::Passed,
+            last_scanned_lines: 0,
+        };
+
+        incremental_validator.line_starts = Self::line_starts_of(source_code);
+        incremental_validator.scan_from(0, None);
+        incremental_validator
+    }
+
+    ///
+    pub fn source_code(&self) -> &str {
+        &self.source_code
+    }
+
+    ///
+    pub fn result(&self) -> &CodeTokenValidateResult {
+        &self.result
+    }
+
+    ///
+    pub fn last_scanned_lines(&self) -> usize {
+        self.last_scanned_lines
+    }
+
+    /// Apply the edit to the source and get back the updated result.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `range` is out of the source or not on `char` boundaries, just like
+    /// `String::replace_range`.
+    pub fn apply_edit(&mut self, edit: &TextEdit) -> &CodeTokenValidateResult {
+        let start_line = self.line_index_of(edit.range.start);
+        let old_end_line = self.line_index_of(edit.range.end);
+
+        self.source_code
+            .replace_range(edit.range.clone(), &edit.new_text);
+        self.line_starts = Self::line_starts_of(&self.source_code);
+
+        let new_end_line = self.line_index_of(edit.range.start + edit.new_text.len());
+        let shift = EditShift {
+            old_end: edit.range.end,
+            old_end_line,
+            line_delta: new_end_line as isize - old_end_line as isize,
+            byte_delta: edit.new_text.len() as isize - edit.range.len() as isize,
+        };
+
+        // The previous check failed before the edited line, nothing changes
+        if start_line >= self.checkpoints.len() {
+            self.last_scanned_lines = 0;
+            return &self.result;
+        }
+
+        self.scan_from(start_line, Some((new_end_line, shift)));
+        &self.result
+    }
+
+    ///
+    fn line_starts_of(source_code: &str) -> Vec<usize> {
+        let mut line_starts = vec![0];
+        line_starts.extend(source_code.match_indices('\n').map(|(index, _)| index + 1));
+        line_starts
+    }
+
+    /// Same as `check_reader`, the empty tail after the last `\n` isn't a line.
+    fn line_count(&self) -> usize {
+        if self.source_code.is_empty() || self.source_code.ends_with('\n') {
+            self.line_starts.len() - 1
+        } else {
+            self.line_starts.len()
+        }
+    }
+
+    /// 0-based line index which contains the `byte_offset`
+    fn line_index_of(&self, byte_offset: usize) -> usize {
+        match self.line_starts.binary_search(&byte_offset) {
+            Ok(line_index) => line_index,
+            Err(line_index) => line_index - 1,
+        }
+    }
+
+    /// Re-check from `start_line`. With an edit, stop as soon as the state after the edited
+    /// lines is the same as the previous check.
+    fn scan_from(&mut self, start_line: usize, edit: Option<(usize, EditShift)>) {
+        let old_checkpoints = self.checkpoints.split_off(start_line + 1);
+        let old_result = std::mem::replace(&mut self.result, CodeTokenValidateResult::Passed);
+        let mut state = self.checkpoints[start_line].clone();
+        self.last_scanned_lines = 0;
+
+        for line_index in start_line..self.line_count() {
+            if line_index > start_line {
+                // After the edited lines, try to reuse the previous checkpoints
+                match &edit {
+                    Some((new_end_line, shift)) if line_index > *new_end_line => {
+                        // The old line index is always greater than `start_line` here
+                        let old_line_index = (line_index as isize - shift.line_delta) as usize;
+                        let old_index = old_line_index - start_line - 1;
+                        if old_index < old_checkpoints.len()
+                            && self.shift_state(&old_checkpoints[old_index], shift) == state
+                        {
+                            self.reuse_previous_check(
+                                &old_checkpoints[old_index..],
+                                old_result,
+                                shift,
+                            );
+                            return;
+                        }
+                    }
+                    _ => {}
+                }
+
+                self.checkpoints.push(state.clone());
+            }
+
+            let line_end = self
+                .line_starts
+                .get(line_index + 1)
+                .map(|next_line_start| next_line_start - 1)
+                .unwrap_or(self.source_code.len());
+            let line_start = self.line_starts[line_index];
+            let line = &self.source_code[line_start..line_end];
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            let source_line =
+                SourceLine::new(line, line_index + 1, line_start, self.validator.column_unit);
+
+            self.last_scanned_lines += 1;
+            let temp_result = self.validator.scan_line(&source_line, &mut state);
+            if temp_result != CodeTokenValidateResult::Passed {
+                self.result = temp_result;
+                return;
+            }
+        }
+
+        self.checkpoints.push(state.clone());
+        self.result = self.validator.finish_check(state);
+    }
+
+    /// The rest lines are checked in the same way as the previous check, just shift their
+    /// checkpoints and the result.
+    fn reuse_previous_check(
+        &mut self,
+        old_checkpoints: &[ScanState],
+        old_result: CodeTokenValidateResult,
+        shift: &EditShift,
+    ) {
+        for old_state in old_checkpoints {
+            let new_state = self.shift_state(old_state, shift);
+            self.checkpoints.push(new_state);
+        }
+
+        self.result = match old_result {
+            CodeTokenValidateResult::Passed => CodeTokenValidateResult::Passed,
+            CodeTokenValidateResult::Fail(mut diagnostic) => {
+                diagnostic.primary_span = self.shift_span(diagnostic.primary_span, shift);
+                diagnostic.related_spans = diagnostic
+                    .related_spans
+                    .iter()
+                    .map(|span| self.shift_span(*span, shift))
+                    .collect();
+                CodeTokenValidateResult::Fail(diagnostic)
+            }
+        };
+    }
+
+    ///
+    fn shift_span(&self, span: Span, shift: &EditShift) -> Span {
+        Span {
+            start: self.shift_position(span.start, shift),
+            byte_len: span.byte_len,
+        }
+    }
+
+    /// Move an old position after the edit to the new source, positions before the edit
+    /// don't change.
+    fn shift_position(&self, position: TokenPosition, shift: &EditShift) -> TokenPosition {
+        if position.byte_offset < shift.old_end {
+            return position;
+        }
+
+        let byte_offset = (position.byte_offset as isize + shift.byte_delta) as usize;
+        let line_number = (position.line_number as isize + shift.line_delta) as usize;
+
+        // The rest of the last edited line, the column has to be counted again
+        let column_number = if position.line_number - 1 == shift.old_end_line {
+            let line_start = self.line_starts[line_number - 1];
+            let before_in_line = &self.source_code[line_start..byte_offset];
+            match self.validator.column_unit {
+                ColumnUnit::UnicodeScalar => before_in_line.chars().count() + 1,
+                ColumnUnit::Grapheme => before_in_line.graphemes(true).count() + 1,
+            }
+        } else {
+            position.column_number
+        };
+
+        TokenPosition {
+            line_number,
+            column_number,
+            byte_offset,
+        }
+    }
+
+    ///
+    fn shift_state(&self, old_state: &ScanState, shift: &EditShift) -> ScanState {
+        // `Stack` only can be walked through by popping, the order is reversed twice
+        let mut old_stack = old_state.token_stack.clone();
+        let mut reversed_tokens = Vec::<PairToken>::new();
+        while let Some(pair_token) = old_stack.pop() {
+            reversed_tokens.push(pair_token);
+        }
+
+        let mut token_stack = Stack::<PairToken>::new();
+        for pair_token in reversed_tokens.into_iter().rev() {
+            token_stack.push(PairToken {
+                token: pair_token.token,
+                position: self.shift_position(pair_token.position, shift),
+            });
+        }
+
+        let mode = match &old_state.mode {
+            ScanMode::Code => ScanMode::Code,
+            ScanMode::InString {
+                delimiter,
+                multiline,
+                position,
+            } => ScanMode::InString {
+                delimiter: delimiter.clone(),
+                multiline: *multiline,
+                position: self.shift_position(*position, shift),
+            },
+            ScanMode::InRawString { hashes, position } => ScanMode::InRawString {
+                hashes: *hashes,
+                position: self.shift_position(*position, shift),
+            },
+            ScanMode::InBlockComment {
+                opening,
+                closing,
+                depth,
+                position,
+            } => ScanMode::InBlockComment {
+                opening: opening.clone(),
+                closing: closing.clone(),
+                depth: *depth,
+                position: self.shift_position(*position, shift),
+            },
+        };
+
+        ScanState {
+            token_stack,
+            mode,
+            matched_pairs: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stack::source_code_token_pairing_validator::diagnostic::{
+        Diagnostic, DiagnosticKind, Severity,
+    };
+    use crate::stack::source_code_token_pairing_validator::language_profile::LanguageProfile;
+
+    /// A tiny `xorshift` generator, good enough for picking random edits
+    struct RandomEdits {
+        seed: u64,
+    }
+
+    impl RandomEdits {
+        fn next(&mut self) -> u64 {
+            self.seed ^= self.seed << 13;
+            self.seed ^= self.seed >> 7;
+            self.seed ^= self.seed << 17;
+            self.seed
+        }
+
+        fn below(&mut self, max: usize) -> usize {
+            (self.next() % max as u64) as usize
+        }
+
+        fn edit_of(&mut self, source_code: &str, alphabet: &[&str]) -> TextEdit {
+            let boundaries = source_code
+                .char_indices()
+                .map(|(index, _)| index)
+                .chain(std::iter::once(source_code.len()))
+                .collect::<Vec<usize>>();
+            let start_index = self.below(boundaries.len());
+            let end_index = (start_index + self.below(4)).min(boundaries.len() - 1);
+
+            let mut new_text = String::new();
+            for _ in 0..self.below(4) {
+                new_text.push_str(alphabet[self.below(alphabet.len())]);
+            }
+
+            TextEdit {
+                range: boundaries[start_index]..boundaries[end_index],
+                new_text,
+            }
+        }
+    }
+
+    fn assert_same_as_full_check(profile: LanguageProfile, source_code: &str, alphabet: &[&str]) {
+        let full_validator = CodeTokenPairingValidator::with_profile(profile.clone());
+        let mut validator = IncrementalTokenPairingValidator::new(
+            CodeTokenPairingValidator::with_profile(profile),
+            source_code,
+        );
+        let mut random_edits = RandomEdits {
+            seed: 0x2545_f491_4f6c_dd1d,
+        };
+
+        for _ in 0..2000 {
+            let edit = random_edits.edit_of(validator.source_code(), alphabet);
+            let result = validator.apply_edit(&edit).clone();
+            let expected_result = full_validator.token_pairing_check(validator.source_code());
+            assert_eq!(
+                result,
+                expected_result,
+                "After {:?}:\n{}",
+                edit,
+                validator.source_code()
+            );
+        }
+    }
+
+    #[test]
+    fn new_should_give_the_same_result_as_full_check() {
+        let validator = CodeTokenPairingValidator::new();
+        for source_code in ["", "\n", "{\n", "{\n}\n", "{\n}", "(]\n", "a\n\n)"] {
+            let incremental_validator = IncrementalTokenPairingValidator::new(
+                CodeTokenPairingValidator::new(),
+                source_code,
+            );
+            assert_eq!(
+                incremental_validator.result(),
+                &validator.token_pairing_check(source_code)
+            );
+        }
+    }
+
+    #[test]
+    fn random_edits_should_give_the_same_result_as_full_check() {
+        let alphabet = ["{", "}", "[", "]", "(", ")", "a", " ", "\n", "\r\n"];
+        assert_same_as_full_check(
+            LanguageProfile::default(),
+            "fn main() {\n    let a = [1, 2];\n    if (a) {\n    }\n}\n",
+            &alphabet,
+        );
+    }
+
+    #[test]
+    fn random_edits_with_strings_and_comments_should_give_the_same_result_as_full_check() {
+        let alphabet = [
+            "{", "}", "(", ")", "<", ">", "\"", "'", "/*", "*/", "//", "\\", "r#\"", "\"#", "a",
+            "é", "\n",
+        ];
+        assert_same_as_full_check(
+            LanguageProfile::rust(),
+            "fn main() {\n    /* a (\n */\n    let a: Vec<u8> = vec![];\n    let b = \"{\n\";\n}\n",
+            &alphabet,
+        );
+    }
+
+    #[test]
+    fn edit_should_only_check_the_changed_lines() {
+        let source_code = "fn a() {\n    b();\n}\n".repeat(100);
+        let mut validator =
+            IncrementalTokenPairingValidator::new(CodeTokenPairingValidator::new(), &source_code);
+        assert_eq!(validator.result(), &CodeTokenValidateResult::Passed);
+        assert_eq!(validator.last_scanned_lines(), 300);
+
+        // Rename `b` in line 2, the rest lines reuse the previous check
+        let result = validator.apply_edit(&TextEdit {
+            range: 13..14,
+            new_text: "cc".to_string(),
+        });
+        assert_eq!(result, &CodeTokenValidateResult::Passed);
+        assert_eq!(validator.last_scanned_lines(), 1);
+
+        // Join line 2 and 3
+        let result = validator.apply_edit(&TextEdit {
+            range: 18..19,
+            new_text: " ".to_string(),
+        });
+        assert_eq!(result, &CodeTokenValidateResult::Passed);
+        assert_eq!(validator.last_scanned_lines(), 1);
+        assert_eq!(&validator.source_code()[..23], "fn a() {\n    cc(); }\nfn");
+
+        // Break the balance of line 2, stop at there
+        let result = validator.apply_edit(&TextEdit {
+            range: 15..16,
+            new_text: "".to_string(),
+        });
+        assert_eq!(
+            result,
+            &CodeTokenValidateResult::Fail(Diagnostic {
+                kind: DiagnosticKind::Mismatch,
+                severity: Severity::Error,
+                token: ")".to_string(),
+                pairing_token: "(".to_string(),
+                primary_span: Span {
+                    start: TokenPosition {
+                        line_number: 2,
+                        column_number: 7,
+                        byte_offset: 15,
+                    },
+                    byte_len: 1,
+                },
+                related_spans: vec![Span {
+                    start: TokenPosition {
+                        line_number: 1,
+                        column_number: 8,
+                        byte_offset: 7,
+                    },
+                    byte_len: 1,
+                }],
+                expected: Some("}".to_string()),
+                found: Some(")".to_string()),
+            })
+        );
+        assert_eq!(validator.last_scanned_lines(), 1);
+
+        // Edits after the failure don't change anything
+        let result = validator.apply_edit(&TextEdit {
+            range: 40..40,
+            new_text: "}".to_string(),
+        });
+        assert!(matches!(result, CodeTokenValidateResult::Fail(..)));
+        assert_eq!(validator.last_scanned_lines(), 0);
+    }
+}
@@ -0,0 +1,197 @@
+///
+/// A string literal delimiter, like `"`, `'` or Python's `"""`.
+///
+#[derive(Debug, PartialEq, Clone)]
+pub struct StringDelimiter {
+    // The token which opens and closes the string literal
+    pub delimiter: String,
+
+    // Whether the string literal is allowed to go across multiple lines
+    pub multiline: bool,
+}
+
+impl StringDelimiter {
+    ///
+    pub fn new(delimiter: &str, multiline: bool) -> Self {
+        Self {
+            delimiter: delimiter.to_string(),
+            multiline,
+        }
+    }
+}
+
+///
+/// # `LanguageProfile`
+///
+/// Describes which tokens have to be paired in a particular language, and which
+/// parts of the source code (string literals and comments) should be skipped
+/// when doing the pairing check.
+///
+/// Use one of the built-in profiles (`rust()`, `c()`, `json()`, `python()`), or
+/// create your own one based on the `Default` (only `{}`, `[]` and `()`):
+///
+/// ```
+/// # use data_structure_implementation_by_rust::stack::source_code_token_pairing_validator::{
+/// #     language_profile::{LanguageProfile, StringDelimiter},
+/// # };
+/// let html_template_profile = LanguageProfile {
+///     name: "html-template".to_string(),
+///     pairs: vec![('{', '}'), ('(', ')'), ('<', '>')],
+///     string_delimiters: vec![StringDelimiter::new("\"", true)],
+///     block_comments: vec![("<!--".to_string(), "-->".to_string())],
+///     ..LanguageProfile::default()
+/// };
+/// ```
+///
+#[derive(Debug, PartialEq, Clone)]
+pub struct LanguageProfile {
+    // Profile name, only for display purpose
+    pub name: String,
+
+    // All `(opening, closing)` token pairs to check. Add `('<', '>')` here if
+    // `<` and `>` should always be paired (HTML-ish templates).
+    pub pairs: Vec<(char, char)>,
+
+    // Everything between the string delimiters will be skipped
+    pub string_delimiters: Vec<StringDelimiter>,
+
+    // The char to escape the next char inside a string literal
+    pub escape_char: Option<char>,
+
+    // Support `Rust` raw strings: `r"..."`, `r#"..."#`, `br##"..."##` etc.
+    pub raw_strings: bool,
+
+    // Treat `'` as a `Rust` char literal (`'a'`, `'\n'`) or a lifetime (`'a`)
+    pub char_literals: bool,
+
+    // Line comment prefixes, like `//` or `#`
+    pub line_comments: Vec<String>,
+
+    // Block comment `(opening, closing)` pairs, like `("/*", "*/")`
+    pub block_comments: Vec<(String, String)>,
+
+    // Whether block comments can be nested (`Rust` allows it, `C` doesn't)
+    pub nested_block_comments: bool,
+
+    // Treat `<` and `>` as brackets only when they look like generic
+    // arguments (`Vec<u8>`, `::<T>`), otherwise they're operators.
+    pub generic_angle_brackets: bool,
+}
+
+///
+impl Default for LanguageProfile {
+    fn default() -> Self {
+        Self {
+            name: "plain".to_string(),
+            pairs: vec![('{', '}'), ('[', ']'), ('(', ')')],
+            string_delimiters: vec![],
+            escape_char: None,
+            raw_strings: false,
+            char_literals: false,
+            line_comments: vec![],
+            block_comments: vec![],
+            nested_block_comments: false,
+            generic_angle_brackets: false,
+        }
+    }
+}
+
+///
+impl LanguageProfile {
+    ///
+    pub fn rust() -> Self {
+        Self {
+            name: "rust".to_string(),
+            string_delimiters: vec![StringDelimiter::new("\"", true)],
+            escape_char: Some('\\'),
+            raw_strings: true,
+            char_literals: true,
+            line_comments: vec!["//".to_string()],
+            block_comments: vec![("/*".to_string(), "*/".to_string())],
+            nested_block_comments: true,
+            generic_angle_brackets: true,
+            ..Self::default()
+        }
+    }
+
+    ///
+    pub fn c() -> Self {
+        Self {
+            name: "c".to_string(),
+            string_delimiters: vec![
+                StringDelimiter::new("\"", false),
+                StringDelimiter::new("'", false),
+            ],
+            escape_char: Some('\\'),
+            line_comments: vec!["//".to_string()],
+            block_comments: vec![("/*".to_string(), "*/".to_string())],
+            ..Self::default()
+        }
+    }
+
+    ///
+    pub fn json() -> Self {
+        Self {
+            name: "json".to_string(),
+            pairs: vec![('{', '}'), ('[', ']')],
+            string_delimiters: vec![StringDelimiter::new("\"", false)],
+            escape_char: Some('\\'),
+            ..Self::default()
+        }
+    }
+
+    ///
+    pub fn python() -> Self {
+        Self {
+            name: "python".to_string(),
+            string_delimiters: vec![
+                StringDelimiter::new("\"\"\"", true),
+                StringDelimiter::new("'''", true),
+                StringDelimiter::new("\"", false),
+                StringDelimiter::new("'", false),
+            ],
+            escape_char: Some('\\'),
+            line_comments: vec!["#".to_string()],
+            ..Self::default()
+        }
+    }
+
+    /// Get back the built-in profile by name: `plain`, `rust`, `c`, `json` or `python`.
+    pub fn by_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "plain" => Some(Self::default()),
+            "rust" => Some(Self::rust()),
+            "c" => Some(Self::c()),
+            "json" => Some(Self::json()),
+            "python" => Some(Self::python()),
+            _ => None,
+        }
+    }
+
+    /// Get back the built-in profile by file extension (without the leading `.`).
+    pub fn by_extension(extension: &str) -> Option<Self> {
+        match extension.to_lowercase().as_str() {
+            "rs" => Some(Self::rust()),
+            "c" | "h" | "cc" | "cpp" | "hpp" => Some(Self::c()),
+            "json" => Some(Self::json()),
+            "py" => Some(Self::python()),
+            _ => None,
+        }
+    }
+
+    /// Get back the closing token if `token` is an opening token
+    pub fn closing_token_of(&self, token: char) -> Option<char> {
+        self.pairs
+            .iter()
+            .find(|(opening, _)| *opening == token)
+            .map(|(_, closing)| *closing)
+    }
+
+    /// Get back the opening token if `token` is a closing token
+    pub fn opening_token_of(&self, token: char) -> Option<char> {
+        self.pairs
+            .iter()
+            .find(|(_, closing)| *closing == token)
+            .map(|(opening, _)| *opening)
+    }
+}
@@ -0,0 +1,301 @@
+use super::diagnostic::Span;
+use super::{CodeTokenPairingValidator, CodeTokenValidateResult, PairToken, ScanState};
+
+/// A matched `(opening, closing)` token pair, `depth` is how many pairs are outside of it
+/// (`0` for the outermost pairs).
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct BracketPair {
+    pub opening: Span,
+    pub closing: Span,
+    pub depth: usize,
+}
+
+///
+impl BracketPair {
+    /// Whether the `byte_offset` sits between the opening and closing token. A cursor right
+    /// before the opening token is outside, a cursor right before the closing token is inside.
+    pub fn encloses(&self, byte_offset: usize) -> bool {
+        self.opening.start.byte_offset + self.opening.byte_len <= byte_offset
+            && byte_offset <= self.closing.start.byte_offset
+    }
+}
+
+///
+/// # `PairIndex`
+///
+/// All matched pairs in the source, built from the same scan as `token_pairing_check`, so
+/// the tokens inside string literals and comments are skipped in the same way. It's built
+/// once and then queried many times, e.g. for the editor to jump between pairs or draw the
+/// indentation guides:
+///
+/// ```
+/// # use data_structure_implementation_by_rust::stack::source_code_token_pairing_validator::{
+/// #     language_profile::LanguageProfile, CodeTokenPairingValidator,
+/// # };
+/// let validator = CodeTokenPairingValidator::with_profile(LanguageProfile::rust());
+/// let pair_index = validator.pair_index("fn a() { b(c[0]) }");
+/// assert_eq!(pair_index.nesting_depth_at(13), 3);
+/// ```
+///
+/// If the source has a pairing problem, only the pairs before the failure are indexed, and
+/// `result()` tells the failure.
+///
+#[derive(Debug)]
+pub struct PairIndex {
+    // Sorted by the opening token position
+    pairs: Vec<BracketPair>,
+
+    // `parents[i]` is the closest pair outside of `pairs[i]`
+    parents: Vec<Option<usize>>,
+
+    // Pair indexes sorted by the closing token position
+    closing_order: Vec<usize>,
+
+    result: CodeTokenValidateResult,
+}
+
+///
+impl PairIndex {
+    ///
+    fn new(
+        mut matched_pairs: Vec<(PairToken, PairToken)>,
+        result: CodeTokenValidateResult,
+    ) -> Self {
+        // Pairs are recorded in closing order, re-order them by the opening token
+        matched_pairs.sort_by_key(|(opening, _)| opening.position.byte_offset);
+
+        let mut pairs = Vec::<BracketPair>::with_capacity(matched_pairs.len());
+        let mut parents = Vec::<Option<usize>>::with_capacity(matched_pairs.len());
+
+        // The pairs which are still open at the current opening token, innermost at the end
+        let mut open_pairs = Vec::<usize>::new();
+
+        for (opening, closing) in matched_pairs {
+            while let Some(&last_pair) = open_pairs.last() {
+                if pairs[last_pair].closing.start.byte_offset < opening.position.byte_offset {
+                    open_pairs.pop();
+                } else {
+                    break;
+                }
+            }
+
+            pairs.push(BracketPair {
+                opening: Self::span_of(&opening),
+                closing: Self::span_of(&closing),
+                depth: open_pairs.len(),
+            });
+            parents.push(open_pairs.last().copied());
+            open_pairs.push(pairs.len() - 1);
+        }
+
+        let mut closing_order = (0..pairs.len()).collect::<Vec<usize>>();
+        closing_order.sort_by_key(|pair| pairs[*pair].closing.start.byte_offset);
+
+        PairIndex {
+            pairs,
+            parents,
+            closing_order,
+            result,
+        }
+    }
+
+    ///
+    fn span_of(pair_token: &PairToken) -> Span {
+        Span {
+            start: pair_token.position,
+            byte_len: pair_token.token.len_utf8(),
+        }
+    }
+
+    /// All matched pairs, sorted by the opening token position.
+    pub fn pairs(&self) -> &[BracketPair] {
+        &self.pairs
+    }
+
+    /// The result of the scan, the index is incomplete if it's a `Fail`.
+    pub fn result(&self) -> &CodeTokenValidateResult {
+        &self.result
+    }
+
+    /// If there is an opening or closing token at `byte_offset`, get back the token paired
+    /// with it.
+    pub fn matching_bracket(&self, byte_offset: usize) -> Option<Span> {
+        if let Ok(pair) = self
+            .pairs
+            .binary_search_by_key(&byte_offset, |pair| pair.opening.start.byte_offset)
+        {
+            return Some(self.pairs[pair].closing);
+        }
+
+        self.closing_order
+            .binary_search_by_key(&byte_offset, |pair| {
+                self.pairs[*pair].closing.start.byte_offset
+            })
+            .ok()
+            .map(|index| self.pairs[self.closing_order[index]].opening)
+    }
+
+    /// All pairs enclosing the `byte_offset` (see `BracketPair::encloses`), from the innermost
+    /// to the outermost.
+    pub fn enclosing_pairs(&self, byte_offset: usize) -> Vec<BracketPair> {
+        // The innermost enclosing pair has to be the last pair opened before `byte_offset`, or
+        // one of its parents.
+        let opened_count = self
+            .pairs
+            .partition_point(|pair| pair.opening.start.byte_offset < byte_offset);
+        let mut current_pair = opened_count.checked_sub(1);
+        while let Some(pair) = current_pair {
+            if self.pairs[pair].encloses(byte_offset) {
+                break;
+            }
+            current_pair = self.parents[pair];
+        }
+
+        let mut enclosing_pairs = Vec::<BracketPair>::new();
+        while let Some(pair) = current_pair {
+            enclosing_pairs.push(self.pairs[pair]);
+            current_pair = self.parents[pair];
+        }
+
+        enclosing_pairs
+    }
+
+    /// How many pairs enclose the `byte_offset`
+    pub fn nesting_depth_at(&self, byte_offset: usize) -> usize {
+        self.enclosing_pairs(byte_offset).len()
+    }
+}
+
+///
+impl CodeTokenPairingValidator {
+    /// Scan the source in the same way as `token_pairing_check`, and index all matched pairs
+    /// for querying.
+    pub fn pair_index(&self, source_code: &str) -> PairIndex {
+        let mut state = ScanState::recording_pairs();
+        let mut result = self
+            .scan_reader(source_code.as_bytes(), &mut state)
+            .expect("Reading from a `&str` never fails");
+        let matched_pairs = state.matched_pairs.take().unwrap_or_default();
+        if result == CodeTokenValidateResult::Passed {
+            result = self.finish_check(state);
+        }
+
+        PairIndex::new(matched_pairs, result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stack::source_code_token_pairing_validator::language_profile::LanguageProfile;
+    use crate::stack::source_code_token_pairing_validator::TokenPosition;
+
+    fn span(line_number: usize, column_number: usize, byte_offset: usize) -> Span {
+        Span {
+            start: TokenPosition {
+                line_number,
+                column_number,
+                byte_offset,
+            },
+            byte_len: 1,
+        }
+    }
+
+    #[test]
+    fn matching_bracket_should_jump_between_pairs() {
+        let validator = CodeTokenPairingValidator::with_profile(LanguageProfile::rust());
+        let source_code = "fn a() {\n    let b: Vec<u8> = c[\"]\"];\n}\n";
+        let pair_index = validator.pair_index(source_code);
+        assert_eq!(pair_index.result(), &CodeTokenValidateResult::Passed);
+        assert_eq!(pair_index.pairs().len(), 4);
+
+        assert_eq!(pair_index.matching_bracket(4), Some(span(1, 6, 5)));
+        assert_eq!(pair_index.matching_bracket(5), Some(span(1, 5, 4)));
+        assert_eq!(pair_index.matching_bracket(7), Some(span(3, 1, 38)));
+        assert_eq!(pair_index.matching_bracket(38), Some(span(1, 8, 7)));
+        assert_eq!(pair_index.matching_bracket(23), Some(span(2, 18, 26)));
+        assert_eq!(pair_index.matching_bracket(31), Some(span(2, 27, 35)));
+
+        // Not a token, or inside a string literal
+        assert_eq!(pair_index.matching_bracket(0), None);
+        assert_eq!(pair_index.matching_bracket(33), None);
+    }
+
+    #[test]
+    fn enclosing_pairs_should_go_outward() {
+        let validator = CodeTokenPairingValidator::new();
+        let source_code = "a { b ( c [ d ] e ) f [ g ] } h";
+        let pair_index = validator.pair_index(source_code);
+
+        let outer_pair = BracketPair {
+            opening: span(1, 3, 2),
+            closing: span(1, 29, 28),
+            depth: 0,
+        };
+        let parentheses_pair = BracketPair {
+            opening: span(1, 7, 6),
+            closing: span(1, 19, 18),
+            depth: 1,
+        };
+        let inner_pair = BracketPair {
+            opening: span(1, 11, 10),
+            closing: span(1, 15, 14),
+            depth: 2,
+        };
+        let second_brackets_pair = BracketPair {
+            opening: span(1, 23, 22),
+            closing: span(1, 27, 26),
+            depth: 1,
+        };
+        assert_eq!(
+            pair_index.pairs(),
+            &[
+                outer_pair,
+                parentheses_pair,
+                inner_pair,
+                second_brackets_pair
+            ]
+        );
+
+        assert_eq!(
+            pair_index.enclosing_pairs(12),
+            vec![inner_pair, parentheses_pair, outer_pair]
+        );
+        assert_eq!(
+            pair_index.enclosing_pairs(16),
+            vec![parentheses_pair, outer_pair]
+        );
+        assert_eq!(pair_index.enclosing_pairs(20), vec![outer_pair]);
+        assert_eq!(
+            pair_index.enclosing_pairs(24),
+            vec![second_brackets_pair, outer_pair]
+        );
+        assert_eq!(pair_index.enclosing_pairs(30), vec![]);
+    }
+
+    #[test]
+    fn nesting_depth_should_count_both_sides_of_the_tokens() {
+        let validator = CodeTokenPairingValidator::new();
+        let pair_index = validator.pair_index("{()}");
+
+        let depths = (0..=4)
+            .map(|byte_offset| pair_index.nesting_depth_at(byte_offset))
+            .collect::<Vec<usize>>();
+        assert_eq!(depths, vec![0, 1, 2, 1, 0]);
+    }
+
+    #[test]
+    fn failed_source_should_only_index_the_pairs_before_the_failure() {
+        let validator = CodeTokenPairingValidator::new();
+        let pair_index = validator.pair_index("{ () ( ] }\n[]");
+
+        assert!(matches!(
+            pair_index.result(),
+            CodeTokenValidateResult::Fail(..)
+        ));
+        assert_eq!(pair_index.pairs().len(), 1);
+        assert_eq!(pair_index.matching_bracket(2), Some(span(1, 4, 3)));
+        assert_eq!(pair_index.matching_bracket(0), None);
+        assert_eq!(pair_index.matching_bracket(11), None);
+    }
+}
@@ -2,7 +2,7 @@ use crate::linked_list::single_linked_list::SingleLinkedList;
 use std::fmt::Debug;
 
 /// Stack<T> Last-in-first-out (LIFO)
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Stack<T: Clone + Debug + PartialEq> {
     size: usize,
     inner_list: SingleLinkedList<T>,
@@ -15,7 +15,8 @@ impl<T: Clone + Debug + PartialEq> Stack<T> {
         Stack {
             size: 0usize,
             // We don't need the access backwards, that's why I pick
-            // `SingleLinkedList` rather the `DoubleLinkedList`.
+            // `SingleLinkedList` rather the `DoubleLinkedList`. The top is the list head, so
+            // `push` and `pop` don't walk through the list.
             inner_list: SingleLinkedList::<T>::new(),
         }
     }
@@ -27,7 +28,7 @@ impl<T: Clone + Debug + PartialEq> Stack<T> {
 
     ///
     pub fn pop(&mut self) -> Option<T> {
-        let result = self.inner_list.pop_tail();
+        let result = self.inner_list.pop_head();
         if result.is_some() {
             self.size -= 1;
         }
@@ -35,15 +36,15 @@ impl<T: Clone + Debug + PartialEq> Stack<T> {
         result
     }
 
-    /// `peek_tail` works like `pop_tail`, but it returns the immutable reference to the last node
-    /// data rather than consume it.
+    /// `peek` works like `pop`, but it returns the immutable reference to the top data rather
+    /// than consume it.
     pub fn peek(&mut self) -> Option<&T> {
-        self.inner_list.peek_tail()
+        self.inner_list.get_head()
     }
 
     ///
     pub fn push(&mut self, data: T) {
-        self.inner_list.append(data);
+        self.inner_list.insert_at_head(data);
         self.size += 1;
     }
 
@@ -59,11 +60,22 @@ impl<T: Clone + Debug + PartialEq> Stack<T> {
 
     ///
     pub fn print_stack(&self) {
-        self.inner_list.print_list();
+        self.bottom_to_top_list().print_list();
     }
 
-    ///
+    /// The content string looks like "bottom --> top", or "empty list".
     pub fn get_stack_content(&self) -> String {
-        self.inner_list.get_list_content()
+        self.bottom_to_top_list().get_list_content()
+    }
+
+    /// A copy of `inner_list` in the push order, the top is the last node.
+    fn bottom_to_top_list(&self) -> SingleLinkedList<T> {
+        let mut top_to_bottom_list = self.inner_list.clone();
+        let mut bottom_to_top_list = SingleLinkedList::<T>::new();
+        while let Some(data) = top_to_bottom_list.pop_head() {
+            bottom_to_top_list.insert_at_head(data);
+        }
+
+        bottom_to_top_list
     }
 }
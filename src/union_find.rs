@@ -0,0 +1,146 @@
+use std::cmp::Ordering;
+
+// ----------------- UnionFind (Disjoint-set) ------------------
+
+///
+/// # `UnionFind`
+///
+/// Keeps the elements `0..len` in disjoint sets, each set is represented by one of its elements
+/// (the root). `find` compresses the path and `union` goes by rank, so both of them are almost
+/// `O(1)` amortized.
+///
+/// The element out of `0..len` panics, just like indexing a `Vec`.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnionFind {
+    // The parent of each element, a root is its own parent
+    parents: Vec<usize>,
+
+    // The upper bound of each root tree height
+    ranks: Vec<u8>,
+
+    sets_count: usize,
+}
+
+///
+impl UnionFind {
+    /// Every element is in its own set
+    pub fn new(len: usize) -> Self {
+        UnionFind {
+            parents: (0..len).collect(),
+            ranks: vec![0; len],
+            sets_count: len,
+        }
+    }
+
+    ///
+    pub fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.parents.is_empty()
+    }
+
+    ///
+    pub fn sets_count(&self) -> usize {
+        self.sets_count
+    }
+
+    /// The root of the set which contains `element`
+    pub fn find(&mut self, element: usize) -> usize {
+        let mut root = element;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+
+        // Path compression, all elements on the path point to the root directly
+        let mut current = element;
+        while self.parents[current] != root {
+            let parent = self.parents[current];
+            self.parents[current] = root;
+            current = parent;
+        }
+
+        root
+    }
+
+    /// Merge the sets of `a` and `b`, `false` if they're in the same set already.
+    ///
+    /// The root with the higher rank becomes the new root, or the smaller one if the ranks are
+    /// the same. So the same `union` calls always give back the same roots.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a_root, b_root) = (self.find(a), self.find(b));
+        if a_root == b_root {
+            return false;
+        }
+
+        let (root, child) = match self.ranks[a_root].cmp(&self.ranks[b_root]) {
+            Ordering::Greater => (a_root, b_root),
+            Ordering::Less => (b_root, a_root),
+            Ordering::Equal => (a_root.min(b_root), a_root.max(b_root)),
+        };
+        self.parents[child] = root;
+        if self.ranks[root] == self.ranks[child] {
+            self.ranks[root] += 1;
+        }
+        self.sets_count -= 1;
+
+        true
+    }
+
+    ///
+    pub fn is_connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_should_work() {
+        let mut union_find = UnionFind::new(3);
+        assert_eq!(union_find.len(), 3);
+        assert_eq!(union_find.sets_count(), 3);
+        assert_eq!(union_find.find(2), 2);
+        assert!(!union_find.is_connected(0, 1));
+        assert!(UnionFind::new(0).is_empty());
+    }
+
+    #[test]
+    fn union_should_merge_sets() {
+        let mut union_find = UnionFind::new(6);
+        assert!(union_find.union(0, 1));
+        assert!(union_find.union(2, 3));
+        assert!(union_find.union(1, 3));
+        assert!(!union_find.union(0, 2));
+        assert_eq!(union_find.sets_count(), 3);
+
+        assert!(union_find.is_connected(0, 3));
+        assert!(!union_find.is_connected(0, 4));
+        assert_eq!(union_find.find(3), 0);
+        assert_eq!(union_find.find(5), 5);
+    }
+
+    #[test]
+    fn find_should_compress_the_path() {
+        let mut union_find = UnionFind::new(4);
+        union_find.union(0, 1);
+        union_find.union(2, 3);
+        union_find.union(3, 1);
+
+        // `3 -> 2 -> 0` before `find`
+        assert_eq!(union_find.find(3), 0);
+        assert_eq!(union_find.parents, vec![0, 0, 0, 0]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn out_of_range_element_should_panic() {
+        let mut union_find = UnionFind::new(2);
+        union_find.find(2);
+    }
+}
@@ -0,0 +1,120 @@
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+///
+/// A directory in the temp directory, it's deleted with all its files when the guard is
+/// dropped (even if the test fails).
+///
+struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    /// The path of `file_name` in the directory
+    fn join(&self, file_name: &str) -> PathBuf {
+        self.path.join(file_name)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
+
+/// A new `pairing_check_test_{name}` directory with a good `.rs` and a good `.json` file
+fn create_test_dir(name: &str) -> TempDir {
+    let test_dir = TempDir {
+        path: std::env::temp_dir().join(format!("pairing_check_test_{}", name)),
+    };
+    let _ = std::fs::remove_dir_all(&test_dir.path);
+    std::fs::create_dir_all(&test_dir.path).unwrap();
+
+    std::fs::write(
+        test_dir.join("good.rs"),
+        "fn main() {\n    let v: Vec<u8> = vec![1, 2];\n    println!(\"{:?})\", v);\n}\n",
+    )
+    .unwrap();
+    std::fs::write(test_dir.join("good.json"), "{\"a\": [1, \"]\"]}\n").unwrap();
+    test_dir
+}
+
+fn run_pairing_check(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_pairing-check"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn pairing_check_should_pass_with_glob() {
+    let test_dir = create_test_dir("pass");
+    let pattern = test_dir.join("*").display().to_string();
+
+    let output = run_pairing_check(&[&pattern]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "");
+}
+
+#[test]
+fn pairing_check_should_print_compiler_style_diagnostics() {
+    let test_dir = create_test_dir("text");
+    let bad_file = test_dir.join("bad.py");
+    std::fs::write(&bad_file, "def f(a):\n    return [a, ')']]\n").unwrap();
+
+    let output = run_pairing_check(&[&bad_file.display().to_string()]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        format!(
+            "{}:2:20: error: Missing the pairing token '[' of ']'\n",
+            bad_file.display()
+        )
+    );
+}
+
+#[test]
+fn pairing_check_should_print_json() {
+    let test_dir = create_test_dir("json");
+    let bad_file = test_dir.join("bad.txt");
+    std::fs::write(&bad_file, "(\n").unwrap();
+    let good_file = test_dir.join("good.rs");
+
+    let output = run_pairing_check(&[
+        "--format",
+        "json",
+        &good_file.display().to_string(),
+        &bad_file.display().to_string(),
+    ]);
+    assert_eq!(output.status.code(), Some(1));
+
+    let results: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(results[0]["status"], "passed");
+    assert_eq!(results[1]["status"], "failed");
+    assert_eq!(results[1]["line"], 1);
+    assert_eq!(results[1]["column"], 1);
+    assert_eq!(results[1]["byteOffset"], 0);
+    assert_eq!(results[1]["diagnostic"]["kind"], "Unclosed");
+    assert_eq!(results[1]["diagnostic"]["expected"], ")");
+    assert_eq!(
+        results[1]["message"],
+        "Missing the pairing token ')' of '(' at 1:1"
+    );
+}
+
+#[test]
+fn pairing_check_should_fail_on_bad_arguments_and_missing_files() {
+    assert_eq!(run_pairing_check(&[]).status.code(), Some(2));
+    assert_eq!(
+        run_pairing_check(&["--profile", "cobol", "a.rs"])
+            .status
+            .code(),
+        Some(2)
+    );
+    assert_eq!(
+        run_pairing_check(&["not_exists_source_file.rs"])
+            .status
+            .code(),
+        Some(2)
+    );
+}
@@ -0,0 +1,128 @@
+use data_structure_implementation_by_rust::graph::directed_graph::*;
+use data_structure_implementation_by_rust::graph::shortest_path::ShortestPath;
+use data_structure_implementation_by_rust::graph::undirected_graph::*;
+
+use super::common::create_graph;
+
+///
+/// The example graph in CLRS "The Floyd-Warshall algorithm" (0-based), plus the isolated
+/// node `5`.
+///
+fn create_textbook_graph() -> DirectedGraph<usize, i64> {
+    create_graph(
+        6,
+        &[
+            (0, 1, 3),
+            (0, 2, 8),
+            (0, 4, -4),
+            (1, 3, 1),
+            (1, 4, 7),
+            (2, 1, 4),
+            (3, 0, 2),
+            (3, 2, -5),
+            (4, 3, 6),
+        ],
+    )
+}
+
+#[test]
+fn floyd_warshall_should_give_all_distances() {
+    let graph = create_textbook_graph();
+    let all_pairs = graph.floyd_warshall_all_pairs().unwrap();
+
+    let expected_distances = [
+        [0, 1, -3, 2, -4],
+        [3, 0, -4, 1, -1],
+        [7, 4, 0, 5, 3],
+        [2, -1, -5, 0, -2],
+        [8, 5, 1, 6, 0],
+    ];
+    for (from, row) in expected_distances.iter().enumerate() {
+        for (to, distance) in row.iter().enumerate() {
+            assert_eq!(all_pairs.distance(from, to), Some(*distance));
+        }
+
+        // Same as Bellman-Ford from each node
+        let tree = graph.bellman_ford_shortest_path_tree(from).unwrap();
+        for (to, distance) in tree.distances() {
+            assert_eq!(all_pairs.distance(from, to), Some(distance));
+        }
+    }
+
+    assert_eq!(all_pairs.distance(0, 5), None);
+    assert_eq!(all_pairs.distance(5, 5), Some(0));
+    assert_eq!(all_pairs.distance(0, 6), None);
+}
+
+#[test]
+fn floyd_warshall_should_rebuild_the_paths() {
+    let graph = create_textbook_graph();
+    let all_pairs = graph.floyd_warshall_all_pairs().unwrap();
+
+    assert_eq!(
+        all_pairs.path(0, 1),
+        Some(ShortestPath {
+            cost: 1,
+            nodes: vec![0, 4, 3, 2, 1],
+        })
+    );
+    assert_eq!(
+        all_pairs.path(2, 0),
+        Some(ShortestPath {
+            cost: 7,
+            nodes: vec![2, 1, 3, 0],
+        })
+    );
+    assert_eq!(
+        all_pairs.path(3, 3),
+        Some(ShortestPath {
+            cost: 0,
+            nodes: vec![3],
+        })
+    );
+    assert_eq!(all_pairs.path(5, 0), None);
+
+    // The path cost is the sum of its edge weights
+    for from in 0..5 {
+        for to in 0..5 {
+            let path = all_pairs.path(from, to).unwrap();
+            let edges_cost = path
+                .nodes
+                .windows(2)
+                .map(|pair| graph.edge_weight(pair[0], pair[1]).unwrap())
+                .sum::<i64>();
+            assert_eq!(edges_cost, path.cost);
+        }
+    }
+}
+
+#[test]
+fn floyd_warshall_should_fail_on_negative_cycles() {
+    let mut graph = create_textbook_graph();
+    graph.update_weight(2, 1, 3);
+
+    // `1 -> 3 -> 2 -> 1` costs `-1` now
+    let negative_cycle = graph.floyd_warshall_all_pairs().unwrap_err();
+    let mut cycle_nodes = negative_cycle.nodes;
+    cycle_nodes.sort_unstable();
+    assert_eq!(cycle_nodes, vec![1, 2, 3]);
+
+    // A negative self-loop is a negative cycle of one node
+    let mut graph = create_graph::<DirectedGraph<usize, i64>>(2, &[(0, 1, 1)]);
+    graph.add_edge(1, 1, -1).unwrap();
+    assert_eq!(graph.floyd_warshall_all_pairs().unwrap_err().nodes, vec![1]);
+}
+
+#[test]
+fn floyd_warshall_should_work_on_undirected_graphs() {
+    let mut graph = create_graph::<UndirectedGraph<usize>>(4, &[(0, 1, 1), (1, 2, 2), (0, 2, 5)]);
+    graph.remove_node(3);
+
+    let all_pairs = graph.floyd_warshall_all_pairs().unwrap();
+    assert_eq!(all_pairs.distance(2, 0), Some(3));
+    assert_eq!(
+        all_pairs.path(2, 0).map(|path| path.nodes),
+        Some(vec![2, 1, 0])
+    );
+    assert_eq!(all_pairs.distance(0, 3), None);
+}
@@ -0,0 +1,110 @@
+use data_structure_implementation_by_rust::graph::biconnectivity::*;
+use data_structure_implementation_by_rust::graph::undirected_graph::*;
+use serde::Deserialize;
+
+use super::common::create_graph;
+
+///
+/// ```text
+/// 0 --- 1       4             8 --- 9
+///  \   /       / \
+///   \ /       /   \
+///    2 ---- 3 --- 5 ---- 6       7
+/// ```
+///
+fn create_two_triangles_graph() -> UndirectedGraph<usize> {
+    create_graph(
+        10,
+        &[
+            (0, 1, 1),
+            (1, 2, 1),
+            (2, 0, 1),
+            (2, 3, 1),
+            (3, 4, 1),
+            (4, 5, 1),
+            (5, 3, 1),
+            (5, 6, 1),
+            (9, 8, 1),
+        ],
+    )
+}
+
+fn component(nodes: &[NodeId], edges: &[(NodeId, NodeId)]) -> BiconnectedComponent {
+    BiconnectedComponent {
+        nodes: nodes.to_vec(),
+        edges: edges.to_vec(),
+    }
+}
+
+#[test]
+fn biconnectivity_should_find_bridges_and_articulation_points() {
+    let graph = create_two_triangles_graph();
+
+    assert_eq!(graph.bridges(), vec![(2, 3), (5, 6), (8, 9)]);
+    assert_eq!(graph.articulation_points(), vec![2, 3, 5]);
+    assert_eq!(
+        graph.biconnected_components(),
+        vec![
+            component(&[0, 1, 2], &[(0, 1), (0, 2), (1, 2)]),
+            component(&[2, 3], &[(2, 3)]),
+            component(&[3, 4, 5], &[(3, 4), (3, 5), (4, 5)]),
+            component(&[5, 6], &[(5, 6)]),
+            component(&[8, 9], &[(8, 9)]),
+        ]
+    );
+}
+
+#[test]
+fn root_should_only_be_articulation_point_with_two_children() {
+    let star = create_graph::<UndirectedGraph<usize>>(3, &[(0, 1, 1), (0, 2, 1)]);
+    assert_eq!(star.articulation_points(), vec![0]);
+    assert_eq!(star.bridges(), vec![(0, 1), (0, 2)]);
+
+    // The self-loop doesn't make any difference
+    let mut cycle =
+        create_graph::<UndirectedGraph<usize>>(4, &[(0, 1, 1), (1, 2, 1), (2, 3, 1), (3, 0, 1)]);
+    cycle.add_edge(1, 1, 1).unwrap();
+    assert_eq!(
+        cycle.biconnectivity(),
+        Biconnectivity {
+            bridges: vec![],
+            articulation_points: vec![],
+            biconnected_components: vec![component(
+                &[0, 1, 2, 3],
+                &[(0, 1), (0, 3), (1, 2), (2, 3)]
+            )],
+        }
+    );
+}
+
+#[test]
+fn biconnectivity_should_work_on_a_long_path() {
+    let nodes_len = 100_000;
+    let edges = (1..nodes_len)
+        .map(|index| (index - 1, index, 1))
+        .collect::<Vec<_>>();
+    let graph = create_graph::<UndirectedGraph<usize>>(nodes_len, &edges);
+
+    let biconnectivity = graph.biconnectivity();
+    assert_eq!(biconnectivity.bridges.len(), nodes_len - 1);
+    assert_eq!(biconnectivity.articulation_points.len(), nodes_len - 2);
+    assert_eq!(biconnectivity.biconnected_components.len(), nodes_len - 1);
+}
+
+#[derive(Debug, Deserialize)]
+struct Planet {
+    label: String,
+}
+
+#[test]
+fn biconnectivity_on_alien_map_should_work() {
+    let graph = UndirectedGraph::<Planet>::load_from_file("alien_map_1.txt").unwrap();
+
+    let labels = graph
+        .articulation_points()
+        .into_iter()
+        .map(|node_index| graph.node(node_index).unwrap().label.as_str())
+        .collect::<Vec<&str>>();
+    assert_eq!(labels, vec!["Earth"]);
+    assert_eq!(graph.bridges(), vec![(0, 1), (1, 2)]);
+}
@@ -0,0 +1,75 @@
+use data_structure_implementation_by_rust::graph::undirected_graph::*;
+use std::path::PathBuf;
+
+///
+/// A graph with the nodes `0..nodes_len` (the node data is its `NodeId`) and all `edges`, it
+/// works for any graph type and weight, e.g. `create_graph::<DirectedGraph<usize, i64>>(...)`.
+///
+pub fn create_graph<G: Graph<usize>>(nodes_len: usize, edges: &[(NodeId, NodeId, G::Weight)]) -> G {
+    let mut graph = G::with_all_nodes(vec![]);
+    for index in 0..nodes_len {
+        graph.add_node(index);
+    }
+
+    for (a, b, weight) in edges {
+        graph.add_edge(*a, *b, *weight).unwrap();
+    }
+
+    graph
+}
+
+///
+/// The example graph in the Wikipedia "Dijkstra's algorithm" page (0-based), plus the
+/// isolated node `6`.
+///
+pub fn create_textbook_graph() -> UndirectedGraph<usize> {
+    create_graph(
+        7,
+        &[
+            (0, 1, 7),
+            (0, 2, 9),
+            (0, 5, 14),
+            (1, 2, 10),
+            (1, 3, 15),
+            (2, 3, 11),
+            (2, 5, 2),
+            (3, 4, 6),
+            (4, 5, 9),
+        ],
+    )
+}
+
+///
+/// A file in the temp directory, it's deleted when the guard is dropped (even if the test
+/// fails). The `name` should be unique among all tests, since they run in parallel.
+///
+pub struct TempFile {
+    path: PathBuf,
+}
+
+impl TempFile {
+    /// `name` is the file name in the temp directory, the file isn't created yet.
+    pub fn new(name: &str) -> Self {
+        TempFile {
+            path: std::env::temp_dir().join(name),
+        }
+    }
+
+    /// Same as `new`, and write `content` into it
+    pub fn with_content(name: &str, content: &str) -> Self {
+        let temp_file = Self::new(name);
+        std::fs::write(&temp_file.path, content).unwrap();
+        temp_file
+    }
+
+    /// The full path, e.g. for `load_from_file` or `save_to_file`
+    pub fn path(&self) -> &str {
+        self.path.to_str().unwrap()
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
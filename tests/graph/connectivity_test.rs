@@ -0,0 +1,97 @@
+use data_structure_implementation_by_rust::graph::undirected_graph::*;
+use serde::Deserialize;
+
+use super::common::TempFile;
+
+///
+/// ```text
+/// 0 --- 1       3 --- 4       6
+/// |     |             |
+/// +--2--+             5
+/// ```
+///
+fn create_graph() -> UndirectedGraph<usize> {
+    let mut graph = UndirectedGraph::with_all_nodes(vec![]);
+    for index in 0..7 {
+        graph.add_node(index);
+    }
+
+    for (a, b) in [(0, 1), (1, 2), (2, 0), (4, 3), (4, 5)] {
+        graph.add_edge(a, b, 1).unwrap();
+    }
+
+    graph
+}
+
+#[test]
+fn connected_components_should_give_component_ids() {
+    let graph = create_graph();
+    let components = graph.connected_components();
+
+    assert_eq!(components.count(), 3);
+    assert_eq!(graph.component_count(), 3);
+    assert_eq!(
+        components.component_ids().collect::<Vec<_>>(),
+        vec![(0, 0), (1, 0), (2, 0), (3, 1), (4, 1), (5, 1), (6, 2)]
+    );
+    assert_eq!(components.component_of(5), Some(1));
+    assert_eq!(components.component_of(7), None);
+    assert_eq!(components.nodes_in(1), Some(&[3, 4, 5][..]));
+    assert_eq!(components.nodes_in(3), None);
+    assert_eq!(
+        components.components().collect::<Vec<_>>(),
+        vec![&[0, 1, 2][..], &[3, 4, 5][..], &[6][..]]
+    );
+}
+
+#[test]
+fn connectivity_queries_should_work() {
+    let mut graph = create_graph();
+
+    assert!(!graph.is_connected());
+    assert!(graph.are_connected(0, 2));
+    assert!(graph.are_connected(5, 3));
+    assert!(graph.are_connected(6, 6));
+    assert!(!graph.are_connected(0, 3));
+    assert!(!graph.are_connected(0, 7));
+
+    graph.add_edge(2, 3, 1).unwrap();
+    graph.remove_node(6);
+    assert!(graph.is_connected());
+    assert_eq!(graph.component_count(), 1);
+
+    assert!(UndirectedGraph::<usize>::with_all_nodes(vec![]).is_connected());
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Planet {
+    label: String,
+    is_reachable: bool,
+}
+
+#[test]
+fn reachable_planets_should_be_in_the_home_component() {
+    let map = r#"{ "label": "Alien Home", "isReachable": true } | -> 1(5),
+{ "label": "Earth", "isReachable": true } | -> 0(5), -> 2(8),
+{ "label": "Mars", "isReachable": true } | -> 1(8),
+{ "label": "Pluto", "isReachable": true } |
+{ "label": "Black Hole", "isReachable": false } |
+"#;
+    let map_file = TempFile::with_content("connectivity_test_alien_map.txt", map);
+    let graph = UndirectedGraph::<Planet>::load_from_file(map_file.path()).unwrap();
+
+    let components = graph.connected_components();
+    let (home, _) = graph
+        .find_node(|planet| planet.label == "Alien Home")
+        .unwrap();
+    let home_component = components.component_of(home);
+    let isolated_planets = graph
+        .nodes()
+        .filter(|(node_index, planet)| {
+            planet.is_reachable && components.component_of(*node_index) != home_component
+        })
+        .map(|(_, planet)| planet.label.as_str())
+        .collect::<Vec<&str>>();
+    assert_eq!(isolated_planets, vec!["Pluto"]);
+}
@@ -0,0 +1,119 @@
+use data_structure_implementation_by_rust::graph::cycle::*;
+use data_structure_implementation_by_rust::graph::undirected_graph::*;
+use serde::Deserialize;
+
+use super::common::create_graph;
+
+/// Every two neighbors in the cycle (and the last and the first) should be connected.
+fn assert_is_cycle(graph: &UndirectedGraph<usize>, cycle: &[NodeId]) {
+    for (position, node_index) in cycle.iter().enumerate() {
+        let next_node_index = cycle[(position + 1) % cycle.len()];
+        assert!(graph.has_edge(*node_index, next_node_index));
+    }
+}
+
+#[test]
+fn find_cycle_should_give_the_cycle_nodes() {
+    // `0 - 1 - 2 - 3 - 1` and the separated `4 - 5`
+    let graph = create_graph::<UndirectedGraph<usize>>(
+        6,
+        &[(0, 1, 1), (1, 2, 1), (2, 3, 1), (3, 1, 1), (4, 5, 1)],
+    );
+
+    assert!(graph.has_cycle());
+    let cycle = graph.find_cycle().unwrap();
+    assert_eq!(cycle, vec![1, 2, 3]);
+    assert_is_cycle(&graph, &cycle);
+
+    let mut self_loop_graph = create_graph::<UndirectedGraph<usize>>(3, &[(0, 1, 1), (1, 2, 1)]);
+    assert_eq!(self_loop_graph.find_cycle(), None);
+    self_loop_graph.add_edge(2, 2, 1).unwrap();
+    assert_eq!(self_loop_graph.find_cycle(), Some(vec![2]));
+}
+
+#[test]
+fn tree_and_forest_should_work() {
+    let tree =
+        create_graph::<UndirectedGraph<usize>>(5, &[(0, 1, 1), (0, 2, 1), (2, 3, 1), (2, 4, 1)]);
+    assert!(tree.is_tree());
+    assert!(tree.is_forest());
+    assert!(!tree.has_cycle());
+
+    let mut forest = create_graph::<UndirectedGraph<usize>>(5, &[(0, 1, 1), (2, 3, 1), (3, 4, 1)]);
+    assert!(!forest.is_tree());
+    assert!(forest.is_forest());
+
+    forest.add_edge(2, 4, 1).unwrap();
+    assert!(!forest.is_forest());
+
+    let empty_graph = create_graph::<UndirectedGraph<usize>>(0, &[]);
+    assert!(!empty_graph.is_tree());
+    assert!(empty_graph.is_forest());
+    assert!(create_graph::<UndirectedGraph<usize>>(1, &[]).is_tree());
+}
+
+#[test]
+fn bipartition_should_give_two_sides() {
+    // An even cycle `0 - 1 - 2 - 3 - 0`, and `4 - 5`
+    let graph = create_graph::<UndirectedGraph<usize>>(
+        6,
+        &[(0, 1, 1), (1, 2, 1), (2, 3, 1), (3, 0, 1), (5, 4, 1)],
+    );
+
+    assert_eq!(
+        graph.bipartition(),
+        Bipartition::TwoSides {
+            left: vec![0, 2, 4],
+            right: vec![1, 3, 5],
+        }
+    );
+}
+
+#[test]
+fn bipartition_should_give_an_odd_cycle_witness() {
+    // A 5-node cycle with a tail: `6 - 0 - 1 - 2 - 3 - 4 - 0`
+    let graph = create_graph::<UndirectedGraph<usize>>(
+        7,
+        &[
+            (6, 0, 1),
+            (0, 1, 1),
+            (1, 2, 1),
+            (2, 3, 1),
+            (3, 4, 1),
+            (4, 0, 1),
+        ],
+    );
+
+    match graph.bipartition() {
+        Bipartition::OddCycle(cycle) => {
+            assert_eq!(cycle.len(), 5);
+            assert_eq!(cycle[0], 0);
+            assert_is_cycle(&graph, &cycle);
+        }
+        two_sides => panic!("Expect an odd cycle, got {:?}", two_sides),
+    }
+
+    let mut self_loop_graph = create_graph::<UndirectedGraph<usize>>(2, &[(0, 1, 1)]);
+    self_loop_graph.add_edge(1, 1, 1).unwrap();
+    assert_eq!(
+        self_loop_graph.bipartition(),
+        Bipartition::OddCycle(vec![1])
+    );
+}
+
+#[derive(Debug, Deserialize)]
+struct Planet {}
+
+#[test]
+fn alien_map_should_be_a_tree() {
+    let graph = UndirectedGraph::<Planet>::load_from_file("alien_map_1.txt").unwrap();
+
+    assert!(graph.is_tree());
+    assert_eq!(
+        graph.bipartition(),
+        Bipartition::TwoSides {
+            left: vec![0, 2],
+            right: vec![1],
+        }
+    );
+}
@@ -0,0 +1,305 @@
+use data_structure_implementation_by_rust::graph::directed_graph::*;
+use data_structure_implementation_by_rust::graph::graph_load_error::GraphLoadError;
+use data_structure_implementation_by_rust::graph::shortest_path::ShortestPath;
+use data_structure_implementation_by_rust::graph::undirected_graph::*;
+use serde::Deserialize;
+
+use super::common::TempFile;
+
+#[derive(Debug, Clone, Deserialize)]
+struct Quest {
+    label: String,
+}
+
+fn load_graph_from_str(name: &str, content: &str) -> Result<DirectedGraph<Quest>, GraphLoadError> {
+    let graph_file = TempFile::with_content(&format!("directed_graph_test_{}.txt", name), content);
+    DirectedGraph::<Quest>::load_from_file(graph_file.path())
+}
+
+///
+/// ```text
+/// Tutorial --(1)--> Find Earth --(2)--> Final
+///     |                                   ^
+///     +----(1)----> Find Mars ----(5)-----+
+/// ```
+///
+fn load_quest_graph() -> DirectedGraph<Quest> {
+    load_graph_from_str(
+        "quests",
+        r#"{ "label": "Tutorial" } | -> 1(1), -> 2(1),
+{ "label": "Find Earth" } | -> 3(2),
+{ "label": "Find Mars" } | -> 3(5),
+{ "label": "Final" } |
+"#,
+    )
+    .unwrap()
+}
+
+fn labels_of<'a>(neighbors: impl Iterator<Item = (NodeId, usize, &'a Quest)>) -> Vec<&'a str> {
+    neighbors
+        .map(|(_, _, quest)| quest.label.as_str())
+        .collect()
+}
+
+#[test]
+fn load_directed_graph_should_work() {
+    let graph = load_quest_graph();
+
+    assert_eq!(graph.nodes_len(), 4);
+    assert_eq!(graph.edges_len(), 4);
+    assert!(graph.has_edge(0, 1));
+    assert!(!graph.has_edge(1, 0));
+    assert_eq!(graph.edge_weight(2, 3), Some(5));
+    assert_eq!(graph.edge_weight(3, 2), None);
+
+    assert_eq!(
+        labels_of(graph.out_neighbors(0)),
+        vec!["Find Earth", "Find Mars"]
+    );
+    assert_eq!(labels_of(graph.out_neighbors(3)), Vec::<&str>::new());
+    assert_eq!(
+        labels_of(graph.in_neighbors(3)),
+        vec!["Find Earth", "Find Mars"]
+    );
+    assert_eq!(graph.out_degree(0), Some(2));
+    assert_eq!(graph.in_degree(0), Some(0));
+    assert_eq!(graph.in_degree(3), Some(2));
+    assert_eq!(graph.in_degree(4), None);
+}
+
+#[test]
+fn load_directed_graph_should_report_errors() {
+    let error =
+        load_graph_from_str("dangling", "{ \"label\": \"Tutorial\" } | -> 1(1),\n").unwrap_err();
+    assert!(matches!(
+        error,
+        GraphLoadError::DanglingEdge {
+            line_number: 1,
+            node_index: 1,
+            nodes_len: 1,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn load_directed_graph_with_signed_and_float_weights_should_work() {
+    let graph_file = TempFile::with_content(
+        "directed_graph_test_signed_weights.txt",
+        r#"{ "label": "Tutorial" } | -> 1(-3),
+{ "label": "Final" } | -> 0(2.5),
+"#,
+    );
+    let graph_filename = graph_file.path();
+
+    let float_graph = DirectedGraph::<Quest, f64>::load_from_file(graph_filename).unwrap();
+    assert_eq!(float_graph.edge_weight(0, 1), Some(-3.0));
+    assert_eq!(float_graph.edge_weight(1, 0), Some(2.5));
+
+    // `2.5` isn't an `i64`, and `-3` isn't a `usize`
+    let signed_error = DirectedGraph::<Quest, i64>::load_from_file(graph_filename).unwrap_err();
+    assert!(matches!(
+        signed_error,
+        GraphLoadError::InvalidEdge { line_number: 2, .. }
+    ));
+    let unsigned_error = DirectedGraph::<Quest>::load_from_file(graph_filename).unwrap_err();
+    assert!(matches!(
+        unsigned_error,
+        GraphLoadError::InvalidEdge { line_number: 1, .. }
+    ));
+
+    let nan_graph_file = TempFile::with_content(
+        "directed_graph_test_nan_weight.txt",
+        "{ \"label\": \"Tutorial\" } | -> 0(NaN),\n",
+    );
+    let nan_error = DirectedGraph::<Quest, f64>::load_from_file(nan_graph_file.path()).unwrap_err();
+    assert!(matches!(
+        nan_error,
+        GraphLoadError::InvalidEdge { line_number: 1, .. }
+    ));
+}
+
+#[test]
+fn directed_graph_algorithms_should_follow_the_edge_direction() {
+    let graph = load_quest_graph();
+
+    assert_eq!(
+        graph.shortest_path(0, 3),
+        Some(ShortestPath {
+            cost: 3,
+            nodes: vec![0, 1, 3],
+        })
+    );
+    assert_eq!(graph.shortest_path(3, 0), None);
+    assert_eq!(graph.bfs(1).count(), 2);
+
+    let reversed_graph = graph.reversed();
+    assert_eq!(
+        reversed_graph.shortest_path(3, 0),
+        Some(ShortestPath {
+            cost: 3,
+            nodes: vec![3, 1, 0],
+        })
+    );
+    assert_eq!(
+        labels_of(reversed_graph.out_neighbors(3)),
+        vec!["Find Earth", "Find Mars"]
+    );
+    assert_eq!(
+        labels_of(reversed_graph.in_neighbors(3)),
+        Vec::<&str>::new()
+    );
+
+    let into_reversed_graph = graph.into_reversed();
+    assert_eq!(into_reversed_graph.edge_weight(3, 2), Some(5));
+    assert_eq!(into_reversed_graph.bfs(3).count(), 4);
+}
+
+#[test]
+fn directed_graph_mutation_should_keep_in_edges() {
+    let mut graph = load_quest_graph();
+
+    assert!(graph.add_edge(3, 0, 9).is_ok());
+    assert!(graph.add_edge(3, 0, 9).is_err());
+    assert!(graph.add_edge(1, 0, 4).is_ok());
+    assert_eq!(graph.in_degree(0), Some(2));
+
+    assert_eq!(graph.update_weight(1, 0, 7), Some(4));
+    assert_eq!(
+        graph
+            .in_neighbors(0)
+            .map(|(node_index, weight, _)| (node_index, weight))
+            .collect::<Vec<_>>(),
+        vec![(3, 9), (1, 7)]
+    );
+
+    assert_eq!(graph.remove_edge(3, 0), Some(9));
+    assert_eq!(graph.remove_edge(3, 0), None);
+    assert_eq!(graph.in_degree(0), Some(1));
+
+    assert_eq!(
+        graph.remove_node(1).map(|quest| quest.label),
+        Some("Find Earth".to_string())
+    );
+    assert_eq!(graph.in_degree(0), Some(0));
+    assert_eq!(graph.in_degree(3), Some(1));
+    assert_eq!(graph.out_degree(0), Some(1));
+    assert_eq!(graph.edges_len(), 2);
+    assert_eq!(graph.nodes_len(), 3);
+
+    let id = graph.add_node(Quest {
+        label: "Epilogue".to_string(),
+    });
+    assert_eq!(id, 4);
+    assert!(graph.add_edge(3, id, 1).is_ok());
+    assert_eq!(labels_of(graph.in_neighbors(id)), vec!["Final"]);
+}
+
+#[test]
+fn conversion_between_directed_and_undirected_should_work() {
+    let mut graph = load_quest_graph();
+    graph.add_edge(3, 1, 1).unwrap();
+    graph.add_edge(2, 2, 3).unwrap();
+    graph.remove_node(0);
+
+    let undirected_graph = UndirectedGraph::from(graph);
+    assert_eq!(undirected_graph.nodes_len(), 3);
+    assert!(undirected_graph.node(0).is_none());
+    // `1 -> 3 (2)` and `3 -> 1 (1)` become one edge with the smaller weight
+    assert_eq!(undirected_graph.edge_weight(1, 3), Some(1));
+    assert_eq!(undirected_graph.edge_weight(3, 1), Some(1));
+    assert_eq!(undirected_graph.edge_weight(3, 2), Some(5));
+    assert_eq!(undirected_graph.edge_weight(2, 2), Some(3));
+    // Counted from both sides, except the self-loop
+    assert_eq!(undirected_graph.edges_len(), 5);
+
+    let directed_graph = DirectedGraph::from(undirected_graph);
+    assert_eq!(directed_graph.edges_len(), 5);
+    assert_eq!(directed_graph.edge_weight(2, 3), Some(5));
+    assert_eq!(directed_graph.edge_weight(3, 2), Some(5));
+    assert_eq!(directed_graph.in_degree(3), Some(2));
+    assert_eq!(directed_graph.in_degree(2), Some(2));
+}
+
+#[test]
+fn nodes_without_data_should_lose_their_in_and_out_edges() {
+    let edge = |node_index| GraphNodeEdge {
+        node_index,
+        weight: 1,
+    };
+    let quest = |label: &str| Quest {
+        label: label.to_string(),
+    };
+    // `0 -> 1 -> 2` and `0 -> 2`, but `1` doesn't have data
+    let graph = DirectedGraph::with_all_nodes(vec![
+        GraphNode {
+            data: Some(quest("Tutorial")),
+            neighbors: vec![edge(1), edge(2)],
+        },
+        GraphNode {
+            data: None,
+            neighbors: vec![edge(2)],
+        },
+        GraphNode {
+            data: Some(quest("Final")),
+            neighbors: vec![],
+        },
+    ]);
+
+    assert_eq!(graph.nodes_len(), 2);
+    assert_eq!(graph.edges_len(), 1);
+    assert_eq!(graph.out_degree(0), Some(1));
+    assert_eq!(graph.in_degree(2), Some(1));
+    assert_eq!(graph.edge_weight(0, 1), None);
+    assert_eq!(graph.edge_weight(1, 2), None);
+}
+
+#[test]
+fn edges_to_missing_nodes_should_be_dropped_by_with_all_nodes() {
+    let edge = |node_index| GraphNodeEdge {
+        node_index,
+        weight: 1,
+    };
+    let quest = |label: &str| Quest {
+        label: label.to_string(),
+    };
+    let create_graph = || {
+        DirectedGraph::with_all_nodes(vec![
+            GraphNode {
+                data: Some(quest("Tutorial")),
+                neighbors: vec![edge(1), edge(7)],
+            },
+            GraphNode {
+                data: Some(quest("Final")),
+                neighbors: vec![],
+            },
+        ])
+    };
+
+    let mut graph = create_graph();
+    assert_eq!(graph.edges_len(), 1);
+    assert_eq!(graph.out_degree(0), Some(1));
+    assert!(format!("{:?}", graph).contains("edges_len: 1"));
+    assert_eq!(UndirectedGraph::from(create_graph()).edges_len(), 2);
+    assert_eq!(
+        graph.remove_node(0).map(|quest| quest.label),
+        Some("Tutorial".to_string())
+    );
+    assert_eq!(graph.in_degree(1), Some(0));
+}
+
+#[derive(Debug, Deserialize)]
+struct Planet {
+    label: String,
+}
+
+#[test]
+fn alien_map_should_load_as_directed_graph() {
+    let graph = DirectedGraph::<Planet>::load_from_file("alien_map_1.txt").unwrap();
+    assert_eq!(graph.edges_len(), 4);
+    assert_eq!(
+        graph.node(2).map(|planet| planet.label.as_str()),
+        Some("Mars")
+    );
+    assert!(format!("{:?}", graph).contains("[1] Some(Planet { label: \"Earth\" })"));
+}
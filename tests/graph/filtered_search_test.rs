@@ -0,0 +1,169 @@
+use data_structure_implementation_by_rust::graph::filtered_search::*;
+use data_structure_implementation_by_rust::graph::shortest_path::ShortestPath;
+use data_structure_implementation_by_rust::graph::undirected_graph::*;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Planet {
+    label: String,
+    is_reachable: bool,
+}
+
+///
+/// ```text
+///         (5)         (8)
+/// Home ------- Earth ------- Mars        Pluto
+///   |                         |
+///   +--(2)--- Moon* ---(2)----+
+/// ```
+///
+/// `*` means not reachable
+///
+fn create_graph() -> UndirectedGraph<Planet> {
+    let mut graph = UndirectedGraph::with_all_nodes(vec![]);
+    for (label, is_reachable) in [
+        ("Home", true),
+        ("Earth", true),
+        ("Mars", true),
+        ("Moon", false),
+        ("Pluto", true),
+    ] {
+        graph.add_node(Planet {
+            label: label.to_string(),
+            is_reachable,
+        });
+    }
+
+    for (a, b, weight) in [(0, 1, 5), (1, 2, 8), (0, 3, 2), (3, 2, 2)] {
+        graph.add_edge(a, b, weight).unwrap();
+    }
+
+    graph
+}
+
+fn is_reachable(_: NodeId, planet: &Planet) -> bool {
+    planet.is_reachable
+}
+
+fn any_node(_: NodeId, _: &Planet) -> bool {
+    true
+}
+
+fn any_edge(_: NodeId, _: NodeId, _: usize) -> bool {
+    true
+}
+
+fn path(cost: usize, nodes: Vec<NodeId>) -> PathSearchOutcome {
+    PathSearchOutcome::Found(ShortestPath { cost, nodes })
+}
+
+#[test]
+fn filtered_search_without_filtering_anything_should_find_the_paths() {
+    let graph = create_graph();
+
+    assert_eq!(
+        graph.bfs_path_filtered(0, 2, any_node, any_edge),
+        path(13, vec![0, 1, 2])
+    );
+    assert_eq!(
+        graph.shortest_path_filtered(0, 2, any_node, any_edge),
+        path(4, vec![0, 3, 2])
+    );
+    assert_eq!(
+        graph
+            .a_star_filtered(0, 2, |_, _| 0, any_node, any_edge)
+            .outcome,
+        path(4, vec![0, 3, 2])
+    );
+    assert_eq!(
+        graph.shortest_path_filtered(1, 1, any_node, any_edge),
+        path(0, vec![1])
+    );
+}
+
+#[test]
+fn filtered_search_should_skip_the_unreachable_nodes() {
+    let graph = create_graph();
+
+    assert_eq!(
+        graph.bfs_path_filtered(0, 2, is_reachable, any_edge),
+        path(13, vec![0, 1, 2])
+    );
+    assert_eq!(
+        graph.shortest_path_filtered(0, 2, is_reachable, any_edge),
+        path(13, vec![0, 1, 2])
+    );
+
+    let result = graph.a_star_filtered(0, 2, |_, _| 0, is_reachable, any_edge);
+    assert_eq!(result.outcome, path(13, vec![0, 1, 2]));
+    assert_eq!(result.outcome.path().map(|path| path.cost), Some(13));
+    assert_eq!(result.nodes_expanded, 3);
+}
+
+#[test]
+fn filtered_search_should_tell_blocked_from_no_path() {
+    let graph = create_graph();
+    let weight_cap = |_: NodeId, _: NodeId, weight: usize| weight <= 5;
+
+    // Earth -> Mars is too heavy, and Moon isn't reachable
+    assert_eq!(
+        graph.bfs_path_filtered(0, 2, is_reachable, weight_cap),
+        PathSearchOutcome::Blocked
+    );
+    assert_eq!(
+        graph.shortest_path_filtered(0, 2, is_reachable, weight_cap),
+        PathSearchOutcome::Blocked
+    );
+    assert_eq!(
+        graph
+            .a_star_filtered(0, 2, |_, _| 0, is_reachable, weight_cap)
+            .outcome,
+        PathSearchOutcome::Blocked
+    );
+
+    // Going to or starting from a filtered out node
+    assert_eq!(
+        graph.shortest_path_filtered(0, 3, is_reachable, any_edge),
+        PathSearchOutcome::Blocked
+    );
+    assert_eq!(
+        graph.bfs_path_filtered(3, 0, is_reachable, any_edge),
+        PathSearchOutcome::Blocked
+    );
+
+    // Pluto isn't connected at all, and there isn't any node `99`
+    assert_eq!(
+        graph.bfs_path_filtered(0, 4, is_reachable, weight_cap),
+        PathSearchOutcome::NoPath
+    );
+    assert_eq!(
+        graph.shortest_path_filtered(0, 4, any_node, any_edge),
+        PathSearchOutcome::NoPath
+    );
+    assert_eq!(
+        graph.a_star_filtered(0, 99, |_, _| 0, any_node, any_edge),
+        FilteredAStarResult {
+            outcome: PathSearchOutcome::NoPath,
+            nodes_expanded: 0,
+        }
+    );
+}
+
+#[test]
+fn filtered_search_on_alien_map_should_work() {
+    let graph = UndirectedGraph::<Planet>::load_from_file("alien_map_1.txt").unwrap();
+    let (home, _) = graph
+        .find_node(|planet| planet.label == "Alien Home")
+        .unwrap();
+    let (mars, _) = graph.find_node(|planet| planet.label == "Mars").unwrap();
+
+    assert_eq!(
+        graph.shortest_path_filtered(home, mars, is_reachable, any_edge),
+        path(13, vec![0, 1, 2])
+    );
+    assert_eq!(
+        graph.shortest_path_filtered(home, mars, is_reachable, |_, _, weight| weight <= 5),
+        PathSearchOutcome::Blocked
+    );
+}
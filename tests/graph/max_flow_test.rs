@@ -0,0 +1,194 @@
+use data_structure_implementation_by_rust::graph::directed_graph::*;
+use data_structure_implementation_by_rust::graph::max_flow::*;
+use data_structure_implementation_by_rust::graph::undirected_graph::*;
+use serde::Deserialize;
+
+use super::common::create_graph;
+
+///
+/// The example flow network in CLRS "Flow networks" (`s, v1, v2, v3, v4, t` are `0..6`)
+///
+fn create_clrs_network() -> DirectedGraph<usize> {
+    create_graph(
+        6,
+        &[
+            (0, 1, 16),
+            (0, 2, 13),
+            (1, 3, 12),
+            (2, 1, 4),
+            (2, 4, 14),
+            (3, 2, 9),
+            (3, 5, 20),
+            (4, 3, 7),
+            (4, 5, 4),
+        ],
+    )
+}
+
+///
+/// The example flow network in the Wikipedia "Edmonds–Karp algorithm" page (`A..G` are
+/// `0..7`)
+///
+fn create_wikipedia_network() -> DirectedGraph<usize> {
+    create_graph(
+        7,
+        &[
+            (0, 1, 3),
+            (0, 3, 3),
+            (1, 2, 4),
+            (2, 0, 3),
+            (2, 3, 1),
+            (2, 4, 2),
+            (3, 4, 2),
+            (3, 5, 6),
+            (4, 1, 1),
+            (4, 6, 1),
+            (5, 6, 9),
+        ],
+    )
+}
+
+/// Each edge flow is within its capacity, and all nodes except `source` and `sink` send out
+/// as much as they get.
+fn assert_valid_flow<T, G>(graph: &G, flow: &MaxFlow)
+where
+    T: std::fmt::Debug + serde::de::DeserializeOwned,
+    G: Graph<T, Weight = usize>,
+{
+    let mut balances = std::collections::BTreeMap::<NodeId, isize>::new();
+    for ((from, to), edge_flow) in flow.edge_flows.iter() {
+        assert!(*edge_flow <= graph.edge_weight(*from, *to).unwrap());
+        *balances.entry(*from).or_default() -= *edge_flow as isize;
+        *balances.entry(*to).or_default() += *edge_flow as isize;
+    }
+
+    for (node_index, balance) in balances {
+        let expected_balance = match node_index {
+            node_index if node_index == flow.source => -(flow.value as isize),
+            node_index if node_index == flow.sink => flow.value as isize,
+            _ => 0,
+        };
+        assert_eq!(balance, expected_balance);
+    }
+
+    let cut_capacity = flow
+        .cut_edges
+        .iter()
+        .map(|(from, to)| graph.edge_weight(*from, *to).unwrap())
+        .sum::<usize>();
+    assert_eq!(cut_capacity, flow.value);
+}
+
+#[test]
+fn max_flow_should_work_on_textbook_networks() {
+    let graph = create_clrs_network();
+    let flow = graph.max_flow(0, 5).unwrap();
+    assert_eq!(flow.value, 23);
+    assert_eq!(flow.source_side, vec![0, 1, 2, 4]);
+    assert_eq!(flow.sink_side, vec![3, 5]);
+    assert_eq!(flow.cut_edges, vec![(1, 3), (4, 3), (4, 5)]);
+    assert_eq!(flow.flow_on(1, 3), 12);
+    assert_eq!(flow.flow_on(3, 1), 0);
+    assert_valid_flow(&graph, &flow);
+
+    let graph = create_wikipedia_network();
+    let flow = graph.max_flow(0, 6).unwrap();
+    assert_eq!(flow.value, 5);
+    assert_eq!(flow.source_side, vec![0, 1, 2, 4]);
+    assert_eq!(flow.cut_edges, vec![(0, 3), (2, 3), (4, 6)]);
+    assert_valid_flow(&graph, &flow);
+}
+
+#[test]
+fn max_flow_should_be_deterministic() {
+    let graph = create_wikipedia_network();
+    let flow = graph.max_flow(0, 6).unwrap();
+
+    assert_eq!(
+        flow.edge_flows.into_iter().collect::<Vec<_>>(),
+        vec![
+            ((0, 1), 2),
+            ((0, 3), 3),
+            ((1, 2), 2),
+            ((2, 3), 1),
+            ((2, 4), 1),
+            ((3, 5), 4),
+            ((4, 6), 1),
+            ((5, 6), 4),
+        ]
+    );
+}
+
+#[test]
+fn max_flow_should_handle_unreachable_and_missing_nodes() {
+    let mut graph = create_graph::<DirectedGraph<usize>>(4, &[(0, 1, 3), (2, 1, 5)]);
+
+    let flow = graph.max_flow(0, 2).unwrap();
+    assert_eq!(flow.value, 0);
+    assert!(flow.edge_flows.is_empty());
+    assert_eq!(flow.source_side, vec![0, 1]);
+    assert_eq!(flow.sink_side, vec![2, 3]);
+    assert!(flow.cut_edges.is_empty());
+
+    // Zero capacity can't carry anything
+    graph.add_edge(1, 2, 0).unwrap();
+    assert_eq!(graph.max_flow(0, 2).map(|flow| flow.value), Some(0));
+
+    assert_eq!(graph.max_flow(0, 0), None);
+    assert_eq!(graph.max_flow(0, 4), None);
+    graph.remove_node(2);
+    assert_eq!(graph.max_flow(0, 2), None);
+}
+
+#[test]
+fn max_flow_should_use_both_directions_of_undirected_edges() {
+    //
+    // 0 --(3)-- 1 --(2)-- 3
+    // |         |         |
+    // (2)      (4)       (3)
+    // |         |         |
+    // 2 --------+---(1)-- 4
+    //
+    let mut graph = UndirectedGraph::<usize>::with_all_nodes(vec![]);
+    for index in 0..5 {
+        graph.add_node(index);
+    }
+    for (a, b, weight) in [
+        (0, 1, 3),
+        (0, 2, 2),
+        (1, 2, 4),
+        (1, 3, 2),
+        (2, 4, 1),
+        (3, 4, 3),
+    ] {
+        graph.add_edge(a, b, weight).unwrap();
+    }
+
+    let flow = graph.max_flow(0, 4).unwrap();
+    assert_eq!(flow.value, 3);
+    assert_eq!(flow.cut_edges, vec![(1, 3), (2, 4)]);
+    assert_valid_flow(&graph, &flow);
+
+    // The same network the other way around
+    assert_eq!(graph.max_flow(4, 0).map(|flow| flow.value), Some(3));
+}
+
+#[derive(Debug, Deserialize)]
+struct Planet {
+    label: String,
+}
+
+#[test]
+fn max_flow_on_alien_map_should_work() {
+    let graph = UndirectedGraph::<Planet>::load_from_file("alien_map_1.txt").unwrap();
+    let (home, _) = graph
+        .find_node(|planet| planet.label == "Alien Home")
+        .unwrap();
+    let (mars, _) = graph.find_node(|planet| planet.label == "Mars").unwrap();
+
+    let flow = graph.max_flow(home, mars).unwrap();
+    assert_eq!(flow.value, 5);
+    assert_eq!(flow.source_side, vec![home]);
+    assert_eq!(flow.cut_edges, vec![(home, 1)]);
+    assert_valid_flow(&graph, &flow);
+}
@@ -0,0 +1,300 @@
+use data_structure_implementation_by_rust::graph::directed_graph::*;
+use data_structure_implementation_by_rust::graph::shortest_path::*;
+use data_structure_implementation_by_rust::graph::undirected_graph::*;
+use serde::Deserialize;
+
+use super::common::{create_graph, create_textbook_graph};
+
+#[test]
+fn shortest_path_should_give_cost_and_nodes() {
+    let graph = create_textbook_graph();
+
+    assert_eq!(
+        graph.shortest_path(0, 4),
+        Some(ShortestPath {
+            cost: 20,
+            nodes: vec![0, 2, 5, 4],
+        })
+    );
+    assert_eq!(
+        graph.shortest_path(3, 0),
+        Some(ShortestPath {
+            cost: 20,
+            nodes: vec![3, 2, 0],
+        })
+    );
+    assert_eq!(
+        graph.shortest_path(1, 1),
+        Some(ShortestPath {
+            cost: 0,
+            nodes: vec![1],
+        })
+    );
+}
+
+#[test]
+fn shortest_path_to_unreachable_node_should_be_none() {
+    let graph = create_textbook_graph();
+
+    assert_eq!(graph.shortest_path(0, 6), None);
+    assert_eq!(graph.shortest_path(0, 7), None);
+    assert_eq!(graph.shortest_path(7, 0), None);
+}
+
+#[test]
+fn shortest_path_tree_should_give_all_distances() {
+    let graph = create_textbook_graph();
+    let tree = graph.shortest_path_tree(0);
+
+    assert_eq!(tree.source(), 0);
+    assert_eq!(
+        tree.distances().collect::<Vec<_>>(),
+        vec![(0, 0), (1, 7), (2, 9), (3, 20), (4, 20), (5, 11)]
+    );
+    assert_eq!(tree.distance_to(6), None);
+    assert_eq!(tree.parent_of(0), None);
+    assert_eq!(tree.parent_of(5), Some(2));
+    assert_eq!(tree.path_to(3).unwrap().nodes, vec![0, 2, 3]);
+    assert_eq!(tree.path_to(6), None);
+}
+
+#[test]
+fn shortest_path_should_break_ties_by_node_index() {
+    // Both `0 -> 1 -> 3` and `0 -> 2 -> 3` cost `2`, no matter in which order the edges are
+    // added, the path through the smaller `NodeId` wins.
+    let graph =
+        create_graph::<UndirectedGraph<usize>>(4, &[(0, 1, 1), (0, 2, 1), (1, 3, 1), (2, 3, 1)]);
+    let reversed_graph =
+        create_graph::<UndirectedGraph<usize>>(4, &[(2, 3, 1), (1, 3, 1), (0, 2, 1), (0, 1, 1)]);
+
+    for graph in [graph, reversed_graph] {
+        assert_eq!(
+            graph.shortest_path(0, 3),
+            Some(ShortestPath {
+                cost: 2,
+                nodes: vec![0, 1, 3],
+            })
+        );
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Planet {
+    label: String,
+}
+
+#[test]
+fn shortest_path_on_alien_map_should_work() {
+    let graph = UndirectedGraph::<Planet>::load_from_file("alien_map_1.txt").unwrap();
+    let (home, _) = graph
+        .find_node(|planet| planet.label == "Alien Home")
+        .unwrap();
+    let (mars, _) = graph.find_node(|planet| planet.label == "Mars").unwrap();
+
+    let path = graph.shortest_path(home, mars).unwrap();
+    assert_eq!(path.cost, 13);
+    let labels = path
+        .nodes
+        .iter()
+        .map(|node_index| graph.node(*node_index).unwrap().label.as_str())
+        .collect::<Vec<&str>>();
+    assert_eq!(labels, vec!["Alien Home", "Earth", "Mars"]);
+}
+
+///
+/// A `width * height` grid, each node is its own `(x, y)` position, all edges' weight is `1`.
+/// The nodes in `walls` don't have any edge.
+///
+fn create_grid(
+    width: isize,
+    height: isize,
+    walls: &[(isize, isize)],
+) -> UndirectedGraph<(isize, isize)> {
+    let mut graph = UndirectedGraph::with_all_nodes(vec![]);
+    for y in 0..height {
+        for x in 0..width {
+            graph.add_node((x, y));
+        }
+    }
+
+    let node_index_of = |x: isize, y: isize| (y * width + x) as NodeId;
+    for y in 0..height {
+        for x in 0..width {
+            if walls.contains(&(x, y)) {
+                continue;
+            }
+            if x + 1 < width && !walls.contains(&(x + 1, y)) {
+                graph
+                    .add_edge(node_index_of(x, y), node_index_of(x + 1, y), 1)
+                    .unwrap();
+            }
+            if y + 1 < height && !walls.contains(&(x, y + 1)) {
+                graph
+                    .add_edge(node_index_of(x, y), node_index_of(x, y + 1), 1)
+                    .unwrap();
+            }
+        }
+    }
+
+    graph
+}
+
+fn position_of(position: &(isize, isize)) -> Option<(isize, isize)> {
+    Some(*position)
+}
+
+#[test]
+fn heuristic_helpers_should_work() {
+    let euclidean = euclidean_distance(position_of);
+    let manhattan = manhattan_distance(position_of);
+    assert_eq!(euclidean(&(0, 0), &(3, 4)), 5);
+    assert_eq!(euclidean(&(1, 1), &(2, 2)), 1);
+    assert_eq!(manhattan(&(0, 0), &(3, -4)), 7);
+
+    let unknown_position = euclidean_distance(|_: &(isize, isize)| None);
+    assert_eq!(unknown_position(&(0, 0), &(3, 4)), 0);
+}
+
+#[test]
+fn a_star_should_find_the_shortest_path_with_fewer_expansions() {
+    // A wall in the middle with a gap at the bottom
+    let graph = create_grid(7, 7, &[(3, 0), (3, 1), (3, 2), (3, 3), (3, 4), (3, 5)]);
+    let (from, to) = (0, 6);
+
+    let dijkstra_like = graph.a_star(from, to, |_, _| 0);
+    let manhattan = graph.a_star(from, to, manhattan_distance(position_of));
+    let euclidean = graph.a_star(from, to, euclidean_distance(position_of));
+
+    let expected_cost = graph.shortest_path(from, to).unwrap().cost;
+    assert_eq!(expected_cost, 18);
+    for result in [&dijkstra_like, &manhattan, &euclidean] {
+        let path = result.path.as_ref().unwrap();
+        assert_eq!(path.cost, expected_cost);
+        assert_eq!(path.nodes.len(), expected_cost + 1);
+        assert_eq!(path.nodes.first(), Some(&from));
+        assert_eq!(path.nodes.last(), Some(&to));
+    }
+
+    assert!(manhattan.nodes_expanded < dijkstra_like.nodes_expanded);
+    assert!(euclidean.nodes_expanded < dijkstra_like.nodes_expanded);
+}
+
+#[test]
+fn a_star_to_unreachable_node_should_give_no_path() {
+    // The wall goes through the entire grid
+    let graph = create_grid(5, 3, &[(2, 0), (2, 1), (2, 2)]);
+
+    let result = graph.a_star(0, 4, manhattan_distance(position_of));
+    assert_eq!(result.path, None);
+    assert_eq!(result.nodes_expanded, 6);
+
+    assert_eq!(
+        graph.a_star(0, 99, manhattan_distance(position_of)),
+        AStarResult {
+            path: None,
+            nodes_expanded: 0,
+        }
+    );
+}
+
+///
+/// The example graph in CLRS "Bellman-Ford algorithm" (`s, t, x, y, z` are `0..5`)
+///
+fn create_bellman_ford_graph() -> DirectedGraph<usize, i64> {
+    create_graph(
+        5,
+        &[
+            (0, 1, 6),
+            (0, 3, 7),
+            (1, 2, 5),
+            (1, 3, 8),
+            (1, 4, -4),
+            (2, 1, -2),
+            (3, 2, -3),
+            (3, 4, 9),
+            (4, 0, 2),
+            (4, 2, 7),
+        ],
+    )
+}
+
+#[test]
+fn bellman_ford_should_work_with_negative_weights() {
+    let graph = create_bellman_ford_graph();
+
+    let tree = graph.bellman_ford_shortest_path_tree(0).unwrap();
+    assert_eq!(
+        tree.distances().collect::<Vec<_>>(),
+        vec![(0, 0), (1, 2), (2, 4), (3, 7), (4, -2)]
+    );
+    assert_eq!(
+        tree.path_to(4),
+        Some(ShortestPath {
+            cost: -2,
+            nodes: vec![0, 3, 2, 1, 4],
+        })
+    );
+    assert_eq!(graph.find_negative_cycle(), None);
+
+    // Same as Dijkstra's algorithm without any negative weight
+    let textbook_graph = create_textbook_graph();
+    assert_eq!(
+        textbook_graph.bellman_ford_shortest_path_tree(0),
+        Ok(textbook_graph.shortest_path_tree(0))
+    );
+}
+
+#[test]
+fn bellman_ford_should_detect_negative_cycles() {
+    // `1 -> 2 -> 3 -> 1` costs `-1`, `4` can't reach it
+    let graph = create_graph::<DirectedGraph<usize, i64>>(
+        5,
+        &[(0, 1, 4), (1, 2, 1), (2, 3, -3), (3, 1, 1), (4, 0, 1)],
+    );
+
+    let negative_cycle = graph.bellman_ford_shortest_path_tree(0).unwrap_err();
+    let mut cycle_nodes = negative_cycle.nodes.clone();
+    cycle_nodes.sort_unstable();
+    assert_eq!(cycle_nodes, vec![1, 2, 3]);
+    let cycle_len = negative_cycle.nodes.len();
+    for (position, from) in negative_cycle.nodes.iter().enumerate() {
+        let to = negative_cycle.nodes[(position + 1) % cycle_len];
+        assert!(graph.has_edge(*from, to));
+    }
+
+    assert!(graph.find_negative_cycle().is_some());
+    let mut graph = graph;
+    graph.remove_edge(4, 0);
+    assert_eq!(
+        graph
+            .bellman_ford_shortest_path_tree(4)
+            .map(|tree| tree.distances().count()),
+        Ok(1)
+    );
+
+    // A negative undirected edge goes back and forth
+    let undirected_graph = create_graph::<UndirectedGraph<usize, i64>>(3, &[(0, 1, 2), (1, 2, -1)]);
+    let negative_cycle = undirected_graph.find_negative_cycle().unwrap();
+    assert_eq!(negative_cycle.nodes.len(), 2);
+    assert!(negative_cycle.nodes.contains(&1) && negative_cycle.nodes.contains(&2));
+}
+
+#[test]
+fn dijkstra_should_work_with_float_weights() {
+    let graph = create_graph::<DirectedGraph<usize, f64>>(
+        4,
+        &[(0, 1, 0.5), (1, 3, 0.25), (0, 2, 0.1), (2, 3, 0.7)],
+    );
+
+    assert_eq!(
+        graph.shortest_path(0, 3),
+        Some(ShortestPath {
+            cost: 0.75,
+            nodes: vec![0, 1, 3],
+        })
+    );
+    assert_eq!(
+        graph.a_star(0, 3, |_, _| 0.0).path.map(|path| path.nodes),
+        Some(vec![0, 1, 3])
+    );
+}
@@ -0,0 +1,130 @@
+use data_structure_implementation_by_rust::graph::spanning_tree::*;
+use data_structure_implementation_by_rust::graph::undirected_graph::*;
+
+use super::common::{create_graph, create_textbook_graph};
+
+fn edge(a: NodeId, b: NodeId, weight: usize) -> SpanningEdge {
+    SpanningEdge { a, b, weight }
+}
+
+#[test]
+fn spanning_forest_should_pick_the_cheapest_edges() {
+    let graph = create_textbook_graph();
+    let expected_forest = SpanningForest {
+        trees: vec![
+            SpanningTree {
+                nodes: vec![0, 1, 2, 3, 4, 5],
+                edges: vec![
+                    edge(2, 5, 2),
+                    edge(3, 4, 6),
+                    edge(0, 1, 7),
+                    edge(0, 2, 9),
+                    edge(4, 5, 9),
+                ],
+                total_weight: 33,
+            },
+            SpanningTree {
+                nodes: vec![6],
+                edges: vec![],
+                total_weight: 0,
+            },
+        ],
+        total_weight: 33,
+    };
+
+    assert_eq!(graph.prim_spanning_forest(), expected_forest);
+    assert_eq!(graph.kruskal_spanning_forest(), expected_forest);
+    assert!(!expected_forest.is_spanning_tree());
+    assert_eq!(expected_forest.edges().count(), 5);
+}
+
+#[test]
+fn spanning_forest_should_break_ties_by_node_index() {
+    // A square with the same weight everywhere, the edges of the smaller `NodeId` win no
+    // matter in which order they're added.
+    let graph =
+        create_graph::<UndirectedGraph<usize>>(4, &[(0, 1, 1), (1, 2, 1), (2, 3, 1), (3, 0, 1)]);
+    let reversed_graph =
+        create_graph::<UndirectedGraph<usize>>(4, &[(3, 0, 1), (2, 3, 1), (1, 2, 1), (0, 1, 1)]);
+
+    for graph in [graph, reversed_graph] {
+        for forest in [
+            graph.prim_spanning_forest(),
+            graph.kruskal_spanning_forest(),
+        ] {
+            assert!(forest.is_spanning_tree());
+            assert_eq!(
+                forest.trees[0].edges,
+                vec![edge(0, 1, 1), edge(0, 3, 1), edge(1, 2, 1)]
+            );
+        }
+    }
+}
+
+#[test]
+fn spanning_forest_should_skip_self_loops_and_removed_nodes() {
+    let mut graph = create_graph::<UndirectedGraph<usize>>(
+        5,
+        &[(0, 0, 1), (0, 1, 4), (1, 2, 3), (2, 3, 5), (3, 4, 1)],
+    );
+    graph.remove_node(2);
+
+    for forest in [
+        graph.prim_spanning_forest(),
+        graph.kruskal_spanning_forest(),
+    ] {
+        let trees = forest
+            .trees
+            .iter()
+            .map(|tree| (tree.nodes.clone(), tree.edges.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            trees,
+            vec![
+                (vec![0, 1], vec![edge(0, 1, 4)]),
+                (vec![3, 4], vec![edge(3, 4, 1)]),
+            ]
+        );
+        assert_eq!(forest.total_weight, 5);
+    }
+
+    let empty_graph = create_graph::<UndirectedGraph<usize>>(0, &[]);
+    assert_eq!(empty_graph.kruskal_spanning_forest().trees, vec![]);
+    assert_eq!(empty_graph.prim_spanning_forest().total_weight, 0);
+}
+
+#[test]
+fn prim_and_kruskal_should_give_the_same_forest() {
+    // xorshift, so the "random" graphs are the same in every run
+    let mut seed = 0x2545_f491_4f6c_dd1du64;
+    let mut next_random = move |max: u64| {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        (seed % max) as usize
+    };
+
+    for _ in 0..50 {
+        let nodes_len = 1 + next_random(20);
+        let mut graph = create_graph::<UndirectedGraph<usize>>(nodes_len, &[]);
+        for _ in 0..next_random(60) {
+            let (a, b, weight) = (
+                next_random(nodes_len as u64),
+                next_random(nodes_len as u64),
+                next_random(5),
+            );
+            let _ = graph.add_edge(a, b, weight);
+        }
+
+        let forest = graph.kruskal_spanning_forest();
+        assert_eq!(graph.prim_spanning_forest(), forest);
+
+        let nodes_count = forest
+            .trees
+            .iter()
+            .map(|tree| tree.nodes.len())
+            .sum::<usize>();
+        assert_eq!(nodes_count, nodes_len);
+        assert_eq!(forest.edges().count(), nodes_len - forest.trees.len());
+    }
+}
@@ -0,0 +1,118 @@
+use data_structure_implementation_by_rust::graph::directed_graph::*;
+use data_structure_implementation_by_rust::graph::topological::*;
+use data_structure_implementation_by_rust::graph::undirected_graph::*;
+
+use super::common::create_graph;
+
+#[test]
+fn topological_sort_should_put_dependencies_first() {
+    let graph = create_graph::<DirectedGraph<usize>>(
+        6,
+        &[(0, 1, 1), (0, 2, 1), (1, 3, 1), (2, 3, 1), (5, 0, 1)],
+    );
+
+    let order = graph.topological_sort().unwrap();
+    assert_eq!(order, vec![4, 5, 0, 1, 2, 3]);
+    for (from, to) in [(0, 1), (0, 2), (1, 3), (2, 3), (5, 0)] {
+        let position_of = |node_index| order.iter().position(|node| *node == node_index);
+        assert!(position_of(from) < position_of(to));
+    }
+
+    assert_eq!(
+        create_graph::<DirectedGraph<usize>>(0, &[]).topological_sort(),
+        Ok(vec![])
+    );
+}
+
+#[test]
+fn topological_sort_should_give_a_cycle_witness() {
+    // `0 -> 1 -> 2 -> 3 -> 1` and `3 -> 4`
+    let graph = create_graph::<DirectedGraph<usize>>(
+        5,
+        &[(0, 1, 1), (1, 2, 1), (2, 3, 1), (3, 1, 1), (3, 4, 1)],
+    );
+    assert_eq!(
+        graph.topological_sort(),
+        Err(DirectedCycle {
+            nodes: vec![2, 3, 1],
+        })
+    );
+
+    let self_loop_graph = create_graph::<DirectedGraph<usize>>(2, &[(1, 0, 1), (0, 0, 1)]);
+    assert_eq!(
+        self_loop_graph.topological_sort(),
+        Err(DirectedCycle { nodes: vec![0] })
+    );
+}
+
+///
+/// ```text
+/// 0 -> 1 -> 2 -> 0      3 -> 4 -> 5 -> 3      6 <-> 7
+///           |           ^         ^           |
+///           +-----------+         +-----------+
+/// ```
+///
+fn create_three_components_graph() -> DirectedGraph<usize> {
+    create_graph(
+        8,
+        &[
+            (0, 1, 1),
+            (1, 2, 1),
+            (2, 0, 1),
+            (2, 3, 4),
+            (3, 4, 1),
+            (4, 5, 1),
+            (5, 3, 1),
+            (6, 5, 2),
+            (6, 7, 1),
+            (7, 6, 1),
+            (7, 4, 3),
+        ],
+    )
+}
+
+#[test]
+fn strongly_connected_components_should_be_in_topological_order() {
+    let graph = create_three_components_graph();
+    let components = graph.strongly_connected_components();
+
+    assert_eq!(components.count(), 3);
+    assert_eq!(
+        components.components().collect::<Vec<_>>(),
+        vec![&[6, 7][..], &[0, 1, 2][..], &[3, 4, 5][..]]
+    );
+    assert_eq!(components.component_of(1), Some(1));
+    assert_eq!(components.component_of(5), Some(2));
+    assert_eq!(components.component_of(8), None);
+    assert_eq!(components.nodes_in(0), Some(&[6, 7][..]));
+    assert_eq!(components.nodes_in(3), None);
+}
+
+#[test]
+fn condensation_should_be_a_dag() {
+    let graph = create_three_components_graph();
+    let condensation = graph.condensation();
+
+    assert_eq!(condensation.nodes_len(), 3);
+    assert_eq!(condensation.node(1), Some(&vec![0, 1, 2]));
+    assert_eq!(condensation.edges_len(), 2);
+    assert_eq!(condensation.edge_weight(1, 2), Some(4));
+    // `6 -> 5 (2)` and `7 -> 4 (3)` become one edge
+    assert_eq!(condensation.edge_weight(0, 2), Some(2));
+    assert_eq!(condensation.topological_sort(), Ok(vec![0, 1, 2]));
+}
+
+#[test]
+fn strongly_connected_components_should_work_on_a_long_cycle() {
+    let nodes_len = 100_000;
+    let mut edges = (1..nodes_len)
+        .map(|index| (index - 1, index, 1))
+        .collect::<Vec<_>>();
+    edges.push((nodes_len - 1, 0, 1));
+    let graph = create_graph::<DirectedGraph<usize>>(nodes_len, &edges);
+
+    let components = graph.strongly_connected_components();
+    assert_eq!(components.count(), 1);
+    assert_eq!(components.nodes_in(0).unwrap().len(), nodes_len);
+    assert_eq!(graph.topological_sort().unwrap_err().nodes.len(), nodes_len);
+}
@@ -0,0 +1,206 @@
+use data_structure_implementation_by_rust::graph::directed_graph::*;
+use data_structure_implementation_by_rust::graph::traversal::*;
+use data_structure_implementation_by_rust::graph::undirected_graph::*;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+///
+/// ```text
+/// 0 --- 1
+/// |     |
+/// 2 --- 3 --- 4       5
+/// ```
+///
+fn create_graph() -> UndirectedGraph<String> {
+    let mut graph = UndirectedGraph::with_all_nodes(vec![]);
+    for label in ["A", "B", "C", "D", "E", "F"] {
+        graph.add_node(label.to_string());
+    }
+
+    for (a, b) in [(0, 1), (0, 2), (1, 3), (2, 3), (3, 4)] {
+        graph.add_edge(a, b, 1).unwrap();
+    }
+
+    graph
+}
+
+fn visit(node_index: NodeId, depth: usize, parent: Option<NodeId>, order: usize) -> TraversalVisit {
+    TraversalVisit {
+        node_index,
+        depth,
+        parent,
+        order,
+    }
+}
+
+#[test]
+fn bfs_should_visit_by_layers() {
+    let graph = create_graph();
+
+    let visits = graph.bfs(0).collect::<Vec<TraversalVisit>>();
+    assert_eq!(
+        visits,
+        vec![
+            visit(0, 0, None, 0),
+            visit(1, 1, Some(0), 1),
+            visit(2, 1, Some(0), 2),
+            visit(3, 2, Some(1), 3),
+            visit(4, 3, Some(3), 4),
+        ]
+    );
+}
+
+#[test]
+fn dfs_should_visit_in_pre_order() {
+    let graph = create_graph();
+
+    let visits = graph.dfs(0).collect::<Vec<TraversalVisit>>();
+    assert_eq!(
+        visits,
+        vec![
+            visit(0, 0, None, 0),
+            visit(1, 1, Some(0), 1),
+            visit(3, 2, Some(1), 2),
+            visit(2, 3, Some(3), 3),
+            visit(4, 3, Some(3), 4),
+        ]
+    );
+}
+
+#[test]
+fn dfs_events_should_give_discover_and_finish_times() {
+    let graph = create_graph();
+
+    let events = graph
+        .dfs_events(0)
+        .map(|event| match event {
+            DfsEvent::Discover { visit, time } => (visit.node_index, "discover", time),
+            DfsEvent::Finish { node_index, time } => (node_index, "finish", time),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        events,
+        vec![
+            (0, "discover", 0),
+            (1, "discover", 1),
+            (3, "discover", 2),
+            (2, "discover", 3),
+            (2, "finish", 4),
+            (4, "discover", 5),
+            (4, "finish", 6),
+            (3, "finish", 7),
+            (1, "finish", 8),
+            (0, "finish", 9),
+        ]
+    );
+}
+
+#[test]
+fn traversal_should_be_lazy_and_stop_at_the_component() {
+    let graph = create_graph();
+
+    let mut bfs = graph.bfs(0);
+    assert_eq!(bfs.next().map(|visit| visit.node_index), Some(0));
+    assert_eq!(bfs.next().map(|visit| visit.node_index), Some(1));
+
+    assert_eq!(graph.bfs(5).count(), 1);
+    assert_eq!(graph.dfs_events(5).count(), 2);
+    assert_eq!(graph.bfs(6).count(), 0);
+    assert_eq!(graph.dfs(6).count(), 0);
+}
+
+#[test]
+fn dfs_should_work_on_a_long_path() {
+    let mut graph = UndirectedGraph::<usize>::with_all_nodes(vec![]);
+    let nodes_len = 100_000;
+    for index in 0..nodes_len {
+        graph.add_node(index);
+        if index > 0 {
+            graph.add_edge(index - 1, index, 1).unwrap();
+        }
+    }
+
+    let last_visit = graph.dfs(0).last().unwrap();
+    assert_eq!(last_visit.node_index, nodes_len - 1);
+    assert_eq!(last_visit.depth, nodes_len - 1);
+}
+
+#[test]
+fn dfs_events_forest_should_discover_each_node_once() {
+    let mut graph = DirectedGraph::<usize>::with_all_nodes(vec![]);
+    for index in 0..4 {
+        graph.add_node(index);
+    }
+    graph.add_edge(0, 1, 1).unwrap();
+    graph.add_edge(2, 1, 1).unwrap();
+    graph.add_edge(3, 2, 1).unwrap();
+
+    let events = DfsEvents::forest(&graph)
+        .map(|event| match event {
+            DfsEvent::Discover { visit, time } => (visit.node_index, "discover", time),
+            DfsEvent::Finish { node_index, time } => (node_index, "finish", time),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        events,
+        vec![
+            (0, "discover", 0),
+            (1, "discover", 1),
+            (1, "finish", 2),
+            (0, "finish", 3),
+            (2, "discover", 4),
+            (2, "finish", 5),
+            (3, "discover", 6),
+            (3, "finish", 7),
+        ]
+    );
+}
+
+///
+/// The global allocator of the test binary, it counts the bytes still allocated by each
+/// thread, so a test can check that it doesn't leak while other tests run in parallel.
+///
+struct CountingAllocator;
+
+thread_local! {
+    static LIVE_BYTES: Cell<isize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = LIVE_BYTES.try_with(|bytes| bytes.set(bytes.get() + layout.size() as isize));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        let _ = LIVE_BYTES.try_with(|bytes| bytes.set(bytes.get() - layout.size() as isize));
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn live_bytes() -> isize {
+    LIVE_BYTES.with(|bytes| bytes.get())
+}
+
+#[test]
+fn dropping_a_partly_consumed_dfs_should_free_its_stack() {
+    let graph = create_graph();
+    let live_bytes_before = live_bytes();
+
+    // All of them stop with frames (and their pending neighbors) left on the stack
+    let mut dfs_events = graph.dfs_events(0);
+    assert!(dfs_events.nth(3).is_some());
+    drop(dfs_events);
+    assert_eq!(graph.dfs(0).take(3).count(), 3);
+    assert_eq!(
+        graph
+            .dfs(0)
+            .find(|visit| visit.node_index == 3)
+            .map(|visit| visit.depth),
+        Some(2)
+    );
+    assert_eq!(live_bytes(), live_bytes_before);
+}
@@ -1,7 +1,12 @@
+use data_structure_implementation_by_rust::graph::graph_load_error::GraphLoadError;
 use data_structure_implementation_by_rust::graph::undirected_graph::*;
-use serde::Deserialize;
+use data_structure_implementation_by_rust::graph::weight::Weight;
+use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize)]
+use super::common::TempFile;
+
+#[derive(Deserialize, Serialize)]
+#[allow(dead_code)]
 #[serde(rename_all = "camelCase")]
 struct Planet {
     label: Option<String>,
@@ -13,11 +18,7 @@ struct Planet {
 
 impl std::fmt::Debug for Planet {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        let label_str = if self.label.is_some() {
-            self.label.as_ref().unwrap()
-        } else {
-            "No lablel"
-        };
+        let label_str = self.label.as_deref().unwrap_or("No lablel");
         write!(f, "{}", label_str)
     }
 }
@@ -90,19 +91,546 @@ fn create_undirected_graph_should_work() {
 
 #[test]
 fn load_graph_from_file_should_work() {
-    // if let Ok(graph) = UndirectedGraph::<Planet>::load_from_file("alien_map_1.txt") {
+    let graph = UndirectedGraph::<Planet>::load_from_file("alien_map_1.txt").unwrap();
+    println!("loaded graph: {graph:#?}");
+
+    assert_eq!(graph.nodes_len(), 3);
+    assert_eq!(graph.edges_len(), 4);
+}
+
+fn load_graph_from_str(
+    name: &str,
+    content: &str,
+    options: &GraphLoadOptions,
+) -> Result<(UndirectedGraph<Planet>, Vec<GraphLoadError>), GraphLoadError> {
+    let graph_file =
+        TempFile::with_content(&format!("undirected_graph_test_{}.txt", name), content);
+    UndirectedGraph::<Planet>::load_from_file_with_options(graph_file.path(), options)
+}
+
+#[test]
+fn load_graph_should_skip_empty_lines() {
+    let (graph, _) = load_graph_from_str(
+        "empty_lines",
+        "{ \"label\": \"A\" } | -> 1(5),\n\n{ \"label\": \"B|C\" } | -> 0(5),\n",
+        &GraphLoadOptions::default(),
+    )
+    .unwrap();
+
+    assert_eq!(graph.nodes_len(), 2);
+    assert_eq!(graph.edges_len(), 2);
+}
+
+#[test]
+fn load_graph_from_missing_file_should_fail() {
+    let error = UndirectedGraph::<Planet>::load_from_file("no_such_alien_map.txt").unwrap_err();
+    assert!(matches!(
+        error,
+        GraphLoadError::Io {
+            line_number: None,
+            ..
+        }
+    ));
+    assert_eq!(error.line_number(), None);
+}
+
+#[test]
+fn load_graph_should_fail_on_an_unreadable_line_instead_of_stopping_there() {
+    // The second line isn't valid UTF-8, the lines after it must not be silently dropped
+    let graph_file = TempFile::new("undirected_graph_test_unreadable_line.txt");
+    let mut content = b"{ \"label\": \"A\" } | -> 1(5),\n".to_vec();
+    content.extend_from_slice(b"{ \"label\": \"\xFF\" } | -> 0(5),\n");
+    content.extend_from_slice(b"{ \"label\": \"C\" } |\n");
+    std::fs::write(graph_file.path(), content).unwrap();
 
-    // }
+    let error = UndirectedGraph::<Planet>::load_from_file(graph_file.path()).unwrap_err();
+    assert!(matches!(
+        error,
+        GraphLoadError::Io {
+            line_number: Some(2),
+            ..
+        }
+    ));
+}
 
-    match UndirectedGraph::<Planet>::load_from_file("alien_map_1.txt") {
-        Ok(graph) => {
-            println!("loaded graph: {graph:#?}");
+#[test]
+fn load_graph_with_invalid_json_should_fail() {
+    let error = load_graph_from_str(
+        "invalid_json",
+        "{ \"label\": \"A\" } | -> 1(5),\n{ \"label\": B } | -> 0(5),\n",
+        &GraphLoadOptions::default(),
+    )
+    .unwrap_err();
 
-            // assert_eq!(graph.nodes_len(), 3);
-            // assert_eq!(graph.edges_len(), 4);
+    match error {
+        GraphLoadError::InvalidJson {
+            line_number,
+            column_number,
+            ..
+        } => {
+            assert_eq!(line_number, 2);
+            assert_eq!(column_number, 12);
         }
-        Err(error) => {
-            println!(">>> 'load_graph_from_file_should_work' failed by: {error:#?}");
+        _ => panic!("Should be 'InvalidJson', but got: {:?}", error),
+    }
+}
+
+#[test]
+fn load_graph_with_invalid_edge_should_fail() {
+    for (edge, column_number) in [
+        ("-> 1(5", 20),
+        ("1(5)", 20),
+        ("-> )1(", 20),
+        ("-> a(5)", 20),
+        ("-> 1(-5)", 20),
+        ("-> 1(5), -> 2", 29),
+    ] {
+        let content = format!("{{ \"label\": \"A\" }} | {}\n", edge);
+        let error = load_graph_from_str("invalid_edge", &content, &GraphLoadOptions::default())
+            .unwrap_err();
+        match error {
+            GraphLoadError::InvalidEdge {
+                line_number: 1,
+                column_number: error_column_number,
+                ..
+            } => assert_eq!(error_column_number, column_number, "{}", edge),
+            _ => panic!("Should be 'InvalidEdge', but got: {:?}", error),
         }
     }
 }
+
+#[test]
+fn load_graph_with_dangling_edge_should_fail() {
+    let error = load_graph_from_str(
+        "dangling_edge",
+        "{ \"label\": \"A\" } | -> 1(5),\n{ \"label\": \"B\" } | -> 0(5), -> 7(3),\n",
+        &GraphLoadOptions::default(),
+    )
+    .unwrap_err();
+
+    match error {
+        GraphLoadError::DanglingEdge {
+            line_number,
+            column_number,
+            node_index,
+            nodes_len,
+        } => {
+            assert_eq!((line_number, column_number), (2, 29));
+            assert_eq!((node_index, nodes_len), (7, 2));
+        }
+        _ => panic!("Should be 'DanglingEdge', but got: {:?}", error),
+    }
+}
+
+#[test]
+fn load_graph_without_separator_should_fail() {
+    let error = load_graph_from_str(
+        "missing_separator",
+        "{ \"label\": \"A\" } -> 0(5),\n",
+        &GraphLoadOptions::default(),
+    )
+    .unwrap_err();
+
+    match error {
+        GraphLoadError::MissingSeparator {
+            line_number,
+            column_number,
+        } => assert_eq!((line_number, column_number), (1, 26)),
+        _ => panic!("Should be 'MissingSeparator', but got: {:?}", error),
+    }
+    assert_eq!(
+        error.to_string(),
+        "Missing '|' at 1:26, each line should have the following format: '{...JSON data here} | edges_separated_by_comma'"
+    );
+}
+
+const ASYMMETRIC_GRAPH: &str = "{ \"label\": \"A\" } | -> 1(5), -> 2(3),
+{ \"label\": \"B\" } | -> 0(5),
+{ \"label\": \"C\" } |
+";
+
+const MISMATCHED_WEIGHT_GRAPH: &str = "{ \"label\": \"A\" } | -> 1(5),
+{ \"label\": \"B\" } | -> 0(7),
+";
+
+#[test]
+fn load_graph_with_asymmetric_edge_should_fail_by_default() {
+    let error = load_graph_from_str(
+        "asymmetric_edge",
+        ASYMMETRIC_GRAPH,
+        &GraphLoadOptions::default(),
+    )
+    .unwrap_err();
+    match error {
+        GraphLoadError::AsymmetricEdge {
+            line_number,
+            column_number,
+            from_node,
+            to_node,
+        } => {
+            assert_eq!((line_number, column_number), (1, 29));
+            assert_eq!((from_node, to_node), (0, 2));
+        }
+        _ => panic!("Should be 'AsymmetricEdge', but got: {:?}", error),
+    }
+}
+
+#[test]
+fn load_graph_with_asymmetric_edge_should_mirror_or_warn_by_options() {
+    let mirror_options = GraphLoadOptions {
+        asymmetric_edges: AsymmetricEdgePolicy::Mirror,
+        ..GraphLoadOptions::default()
+    };
+    let (graph, warnings) =
+        load_graph_from_str("asymmetric_mirror", ASYMMETRIC_GRAPH, &mirror_options).unwrap();
+    assert_eq!(graph.edges_len(), 4);
+    assert!(warnings.is_empty());
+    assert!(format!("{:?}", graph).contains("[2] Some(C): \"-> Some(A)(3)\""));
+
+    let warn_options = GraphLoadOptions {
+        asymmetric_edges: AsymmetricEdgePolicy::Warn,
+        ..GraphLoadOptions::default()
+    };
+    let (graph, warnings) =
+        load_graph_from_str("asymmetric_warn", ASYMMETRIC_GRAPH, &warn_options).unwrap();
+    assert_eq!(graph.edges_len(), 3);
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].line_number(), Some(1));
+    assert_eq!(
+        warnings[0].to_string(),
+        "Edge at 1:29 connects node 0 to node 2, but node 2 doesn't connect back"
+    );
+}
+
+#[test]
+fn load_graph_with_mismatched_weight_should_fail_by_default() {
+    let error = load_graph_from_str(
+        "mismatched_weight",
+        MISMATCHED_WEIGHT_GRAPH,
+        &GraphLoadOptions::default(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Edge at 2:20 from node 1 to node 0 has weight 7, but the edge back at line 1 has weight 5"
+    );
+}
+
+#[test]
+fn load_graph_with_mismatched_weight_should_use_first_or_warn_by_options() {
+    let use_first_options = GraphLoadOptions {
+        mismatched_weights: MismatchedWeightPolicy::UseFirst,
+        ..GraphLoadOptions::default()
+    };
+    let (graph, warnings) = load_graph_from_str(
+        "mismatched_weight_use_first",
+        MISMATCHED_WEIGHT_GRAPH,
+        &use_first_options,
+    )
+    .unwrap();
+    assert!(warnings.is_empty());
+    assert!(format!("{:?}", graph).contains("[1] Some(B): \"-> Some(A)(5)\""));
+
+    let warn_options = GraphLoadOptions {
+        mismatched_weights: MismatchedWeightPolicy::Warn,
+        ..GraphLoadOptions::default()
+    };
+    let (graph, warnings) = load_graph_from_str(
+        "mismatched_weight_warn",
+        MISMATCHED_WEIGHT_GRAPH,
+        &warn_options,
+    )
+    .unwrap();
+    assert!(format!("{:?}", graph).contains("[1] Some(B): \"-> Some(A)(7)\""));
+    assert!(matches!(
+        warnings[..],
+        [GraphLoadError::MismatchedWeight {
+            line_number: 2,
+            reverse_line_number: 1,
+            ..
+        }]
+    ));
+}
+
+fn planet(label: &str) -> Planet {
+    Planet {
+        label: Some(label.to_string()),
+        draw_color: None,
+        draw_sprite: None,
+        relative_position: None,
+        is_reachable: Some(true),
+    }
+}
+
+#[test]
+fn add_node_and_edge_should_keep_both_directions() {
+    let mut graph = create_graph();
+    let venus = graph.add_node(planet("Venus"));
+    assert_eq!(venus, 3);
+    assert_eq!(graph.nodes_len(), 4);
+    assert_eq!(graph.edges_len(), 4);
+
+    assert_eq!(graph.add_edge(venus, 1, 2), Ok(()));
+    assert_eq!(graph.edges_len(), 6);
+    let debug_str = format!("{:?}", graph);
+    assert!(debug_str.contains("[3] Some(Venus): \"-> Some(Earth)(2)\""));
+    assert!(debug_str.contains("-> Some(Mars)(8), -> Some(Venus)(2)"));
+
+    // Self-loop is only saved once
+    assert_eq!(graph.add_edge(venus, venus, 1), Ok(()));
+    assert_eq!(graph.edges_len(), 7);
+
+    assert_eq!(
+        graph.add_edge(1, venus, 9),
+        Err("Node 1 and 3 are already connected".to_string())
+    );
+    assert_eq!(
+        graph.add_edge(venus, 10, 9),
+        Err("Node 10 doesn't exist".to_string())
+    );
+}
+
+#[test]
+fn remove_and_update_edge_should_change_both_directions() {
+    let mut graph = create_graph();
+
+    assert_eq!(graph.update_weight(2, 1, 10), Some(8));
+    assert!(format!("{:?}", graph).contains("-> Some(Alien Home)(5), -> Some(Mars)(10)"));
+    assert_eq!(graph.update_weight(0, 2, 10), None);
+
+    assert_eq!(graph.remove_edge(0, 1), Some(5));
+    assert_eq!(graph.edges_len(), 2);
+    assert_eq!(graph.remove_edge(1, 0), None);
+    assert!(format!("{:?}", graph).contains("[1] Some(Earth): \"-> Some(Mars)(10)\""));
+}
+
+#[test]
+fn remove_node_should_keep_other_node_indexes() {
+    let mut graph = create_graph();
+
+    let earth = graph.remove_node(1).unwrap();
+    assert_eq!(earth.label.as_deref(), Some("Earth"));
+    assert_eq!(graph.nodes_len(), 2);
+    assert_eq!(graph.edges_len(), 0);
+    assert!(!graph.contains_node(1));
+    assert!(graph.remove_node(1).is_none());
+    assert!(graph.node_data_mut(1).is_none());
+    assert_eq!(
+        graph.add_edge(0, 1, 5),
+        Err("Node 1 doesn't exist".to_string())
+    );
+
+    // Mars is still node 2, and the removed index is never reused
+    assert_eq!(graph.add_edge(0, 2, 7), Ok(()));
+    assert_eq!(graph.add_node(planet("Venus")), 3);
+    assert_eq!(graph.nodes_len(), 3);
+    assert!(!format!("{:?}", graph).contains("Earth"));
+}
+
+#[test]
+fn node_data_mut_should_change_the_node_data() {
+    let mut graph = create_graph();
+    graph.node_data_mut(2).unwrap().label = Some("Red Planet".to_string());
+    assert!(format!("{:?}", graph).contains("[2] Some(Red Planet)"));
+}
+
+fn label_of(planet: &Planet) -> &str {
+    planet.label.as_deref().unwrap_or_default()
+}
+
+#[test]
+fn query_node_and_neighbors_should_work() {
+    let graph = UndirectedGraph::<Planet>::load_from_file("alien_map_1.txt").unwrap();
+
+    assert_eq!(graph.node(1).map(label_of), Some("Earth"));
+    assert!(graph.node(3).is_none());
+
+    let neighbors = graph
+        .neighbors(1)
+        .map(|(node_index, weight, planet)| (node_index, weight, label_of(planet)))
+        .collect::<Vec<_>>();
+    assert_eq!(neighbors, vec![(0, 5, "Alien Home"), (2, 8, "Mars")]);
+    assert_eq!(graph.neighbors(3).count(), 0);
+
+    assert_eq!(graph.degree(0), Some(1));
+    assert_eq!(graph.degree(1), Some(2));
+    assert_eq!(graph.degree(3), None);
+
+    assert!(graph.has_edge(2, 1));
+    assert!(!graph.has_edge(0, 2));
+    assert_eq!(graph.edge_weight(1, 2), Some(8));
+    assert_eq!(graph.edge_weight(0, 2), None);
+}
+
+#[test]
+fn query_nodes_should_skip_removed_nodes() {
+    let mut graph = create_graph();
+    graph.remove_node(0);
+
+    let labels = graph
+        .nodes()
+        .map(|(node_index, planet)| (node_index, label_of(planet)))
+        .collect::<Vec<_>>();
+    assert_eq!(labels, vec![(1, "Earth"), (2, "Mars")]);
+    assert!(graph.node(0).is_none());
+    assert_eq!(graph.neighbors(1).count(), 1);
+}
+
+#[test]
+fn nodes_without_data_should_be_treated_as_removed() {
+    let edge = |node_index| GraphNodeEdge {
+        node_index,
+        weight: 1,
+    };
+    let mut graph = UndirectedGraph::<Planet>::with_all_nodes(vec![
+        GraphNode {
+            data: Some(planet("Earth")),
+            neighbors: vec![edge(1), edge(2)],
+        },
+        GraphNode {
+            data: None,
+            neighbors: vec![edge(0)],
+        },
+        GraphNode {
+            data: Some(planet("Mars")),
+            neighbors: vec![edge(0)],
+        },
+    ]);
+
+    assert_eq!(graph.nodes_len(), graph.nodes().count());
+    assert_eq!(graph.nodes_len(), 2);
+    assert_eq!(graph.edges_len(), 2);
+    assert!(!graph.contains_node(1));
+    assert_eq!(graph.degree(0), Some(1));
+    assert!(graph.add_edge(1, 2, 1).is_err());
+
+    // The edges `0 - 1` are dropped, they don't come back with the node
+    assert_eq!(graph.add_node(planet("Moon")), 3);
+    assert_eq!(graph.edge_weight(0, 1), None);
+    assert_eq!(graph.edge_weight(0, 2), Some(1));
+    assert_eq!(graph.edges_len(), 2);
+}
+
+#[test]
+fn find_node_should_look_up_by_label() {
+    let graph = create_graph();
+
+    let (mars_index, mars) = graph
+        .find_node(|planet| label_of(planet) == "Mars")
+        .unwrap();
+    assert_eq!(mars_index, 2);
+    assert_eq!(mars.relative_position, Some((40, 50)));
+    assert!(graph
+        .find_node(|planet| label_of(planet) == "Pluto")
+        .is_none());
+}
+
+fn graph_to_string<W: Weight>(graph: &UndirectedGraph<Planet, W>) -> String {
+    let mut buffer = Vec::<u8>::new();
+    graph.write_to(&mut buffer).unwrap();
+    String::from_utf8(buffer).unwrap()
+}
+
+#[test]
+fn save_alien_map_should_round_trip() {
+    let graph = UndirectedGraph::<Planet>::load_from_file("alien_map_1.txt").unwrap();
+    let content = graph_to_string(&graph);
+    assert_eq!(
+        content,
+        r##"{"label":"Alien Home","drawColor":"#ACE6FE","drawSprite":"","isReachable":true,"relativePosition":null} | -> 1(5),
+{"label":"Earth","drawColor":"#FF9F1C","drawSprite":"","isReachable":true,"relativePosition":null} | -> 0(5), -> 2(8),
+{"label":"Mars","drawColor":"#FF9F1C","drawSprite":"","isReachable":true,"relativePosition":null} | -> 1(8),
+"##
+    );
+
+    let graph_file = TempFile::new("undirected_graph_test_alien_map_round_trip.txt");
+    let graph_filename = graph_file.path();
+    graph.save_to_file(graph_filename).unwrap();
+    assert_eq!(std::fs::read_to_string(graph_filename).unwrap(), content);
+
+    let loaded_graph = UndirectedGraph::<Planet>::load_from_file(graph_filename).unwrap();
+    assert_eq!(graph_to_string(&loaded_graph), content);
+    assert_eq!(loaded_graph.nodes_len(), 3);
+    assert_eq!(loaded_graph.edge_weight(1, 2), Some(8));
+    assert_eq!(label_of(loaded_graph.node(2).unwrap()), "Mars");
+}
+
+#[test]
+fn save_graph_should_skip_removed_nodes() {
+    let mut graph = UndirectedGraph::<Planet, f64>::with_all_nodes(vec![]);
+    for label in ["Venus", "Earth", "Mars", "Jupiter"] {
+        graph.add_node(planet(label));
+    }
+    graph.add_edge(0, 2, 0.5).unwrap();
+    graph.add_edge(1, 3, 1.25).unwrap();
+    graph.add_edge(2, 3, -3.0).unwrap();
+    graph.add_edge(3, 3, 1e20).unwrap();
+    graph.remove_node(1);
+
+    // `Mars` and `Jupiter` move up by one line
+    let content = graph_to_string(&graph);
+    let edges = content
+        .lines()
+        .map(|line| line.rsplit_once('|').unwrap().1)
+        .collect::<Vec<&str>>();
+    assert_eq!(
+        edges,
+        vec![
+            " -> 1(0.5),",
+            " -> 0(0.5), -> 2(-3),",
+            " -> 1(-3), -> 2(100000000000000000000),"
+        ]
+    );
+
+    let graph_file = TempFile::new("undirected_graph_test_removed_nodes.txt");
+    let graph_filename = graph_file.path();
+    graph.save_to_file(graph_filename).unwrap();
+    let loaded_graph = UndirectedGraph::<Planet, f64>::load_from_file(graph_filename).unwrap();
+    assert_eq!(graph_to_string(&loaded_graph), content);
+    assert_eq!(label_of(loaded_graph.node(2).unwrap()), "Jupiter");
+    assert_eq!(loaded_graph.edge_weight(1, 2), Some(-3.0));
+
+    assert!(graph.save_to_file("/no/such/directory/graph.txt").is_err());
+}
+
+#[test]
+fn save_graph_should_skip_nodes_without_data() {
+    let graph = UndirectedGraph::<Planet>::with_all_nodes(vec![
+        GraphNode {
+            data: None,
+            neighbors: vec![GraphNodeEdge {
+                node_index: 1,
+                weight: 5,
+            }],
+        },
+        GraphNode {
+            data: Some(planet("Earth")),
+            neighbors: vec![
+                GraphNodeEdge {
+                    node_index: 0,
+                    weight: 5,
+                },
+                GraphNodeEdge {
+                    node_index: 2,
+                    weight: 8,
+                },
+            ],
+        },
+        GraphNode {
+            data: Some(planet("Mars")),
+            neighbors: vec![GraphNodeEdge {
+                node_index: 1,
+                weight: 8,
+            }],
+        },
+    ]);
+
+    // `Earth` and `Mars` move up by one line, the edge to the node without data is dropped
+    let content = graph_to_string(&graph);
+    assert_eq!(content.lines().count(), graph.nodes_len());
+    let edges = content
+        .lines()
+        .map(|line| line.rsplit_once('|').unwrap().1)
+        .collect::<Vec<&str>>();
+    assert_eq!(edges, vec![" -> 1(8),", " -> 0(8),"]);
+}
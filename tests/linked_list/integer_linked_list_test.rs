@@ -178,3 +178,27 @@ fn pop_tail_should_work_correctly() {
     integer_list.print_list();
     assert_eq!(integer_list.get_list_content(), "empty list");
 }
+
+#[test]
+fn cloned_integer_list_should_be_equal_but_independent() {
+    let integer_list = create_test_list();
+    let mut cloned_list = integer_list.clone();
+
+    assert_eq!(cloned_list, integer_list);
+    assert_eq!(
+        cloned_list.get_list_content(),
+        "(4 elements): 1 --> 2 --> 3 --> 4"
+    );
+
+    assert_eq!(cloned_list.pop_tail(), Some(4));
+    assert_ne!(cloned_list, integer_list);
+    assert_eq!(integer_list.size(), 4);
+    assert_eq!(integer_list.get_tail(), Some(&4));
+
+    cloned_list.append(5);
+    assert_ne!(cloned_list, integer_list);
+    assert_eq!(
+        SingleLinkedList::<isize>::new(),
+        SingleLinkedList::<isize>::new()
+    );
+}
@@ -140,3 +140,19 @@ fn pop_tail_should_work_correctly() {
     string_list.print_list();
     assert_eq!(string_list.get_list_content(), "empty list");
 }
+
+#[test]
+fn dropping_a_list_should_drop_all_its_data() {
+    let data = std::rc::Rc::new("Rust".to_string());
+    let mut rc_list = SingleLinkedList::<std::rc::Rc<String>>::new();
+    rc_list.append(data.clone());
+    rc_list.append(data.clone());
+    rc_list.insert_at_head(data.clone());
+    assert_eq!(std::rc::Rc::strong_count(&data), 4);
+
+    drop(rc_list.clone());
+    assert_eq!(std::rc::Rc::strong_count(&data), 4);
+
+    drop(rc_list);
+    assert_eq!(std::rc::Rc::strong_count(&data), 1);
+}
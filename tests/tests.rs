@@ -1,3 +1,6 @@
+// The tests compare bools with `assert_eq!` to keep the expected value visible.
+#![allow(clippy::bool_assert_comparison)]
+
 mod linked_list {
     mod integer_linked_list_test;
     mod string_linked_list_test;
@@ -9,5 +12,21 @@ mod stack {
 }
 
 mod graph {
+    mod all_pairs_shortest_path_test;
+    mod biconnectivity_test;
+    mod common;
+    mod connectivity_test;
+    mod cycle_test;
+    mod directed_graph_test;
+    mod filtered_search_test;
+    mod max_flow_test;
+    mod shortest_path_test;
+    mod spanning_tree_test;
+    mod topological_test;
+    mod traversal_test;
     mod undirected_graph_test;
 }
+
+mod bin {
+    mod pairing_check_test;
+}